@@ -11,6 +11,10 @@ use rand::rng::xorshift::*;
 use rand::rng::lcg::*;
 use rand::rng::lfsr::*;
 use rand::rng::mwc::*;
+use rand::rng::pcg::*;
+use rand::rng::splitmix::*;
+use rand::rng::chacha::*;
+use rand::rng::hc128::*;
 
 trait Runner {
     fn run<R: Rng>(&self, R, uint);
@@ -87,10 +91,14 @@ fn str_to_rng<R: Runner>(name: &str, r: R) {
              MT19937: 32, MT19937_64: 64,
              WELL512: 32,
              XorShift4: 32,
+             Xoshiro256StarStar: 64, Xoshiro256Plus: 64, Xoroshiro128Plus: 64,
              LFSR258: 64, LFSR113: 32, Taus88: 32,
              StdRng: 64,
              MinStd_Rand: 32, Rand48: 32,
-             CMWC: 32, MWC256: 32)
+             CMWC: 32, MWC256: 32,
+             Pcg32: 32, Pcg64: 64,
+             SplitMix64: 64,
+             ChaChaRng: 32, Hc128Rng: 32)
 }
 
 fn main() {
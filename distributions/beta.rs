@@ -0,0 +1,183 @@
+use Rng;
+use super::{Sample, Distribution, special};
+use super::{ParameterError, is_positive, unwrap_params};
+use super::gamma::Gamma;
+use std::num;
+
+/// The Beta distribution `Beta(a, b)` on [0, 1], with density
+/// proportional to `x^(a - 1) (1 - x)^(b - 1)`.
+///
+/// This is sampled as `X / (X + Y)` where `X` and `Y` are independent
+/// `Gamma(a, 1)` and `Gamma(b, 1)` random variables.
+pub struct Beta {
+    priv a: f64,
+    priv b: f64,
+    priv gamma_a: Gamma,
+    priv gamma_b: Gamma
+}
+
+impl Beta {
+    /// Construct a `Beta` with shape parameters `a` and `b`. Fails if
+    /// either is not positive and finite.
+    pub fn new(a: f64, b: f64) -> Beta {
+        unwrap_params("Beta", Beta::try_new(a, b))
+    }
+
+    /// Construct a `Beta` with shape parameters `a` and `b`, returning
+    /// an error if either is not positive and finite.
+    pub fn try_new(a: f64, b: f64) -> Result<Beta, ParameterError> {
+        if !is_positive(a) {
+            Err(ParameterError::not_positive("a"))
+        } else if !is_positive(b) {
+            Err(ParameterError::not_positive("b"))
+        } else {
+            Ok(Beta {
+                a: a,
+                b: b,
+                gamma_a: Gamma::new(a, 1.0),
+                gamma_b: Gamma::new(b, 1.0)
+            })
+        }
+    }
+}
+
+impl Sample<f64> for Beta {
+    fn sample<R: Rng>(&self, rng: &mut R) -> f64 {
+        let x = self.gamma_a.sample(rng);
+        let y = self.gamma_b.sample(rng);
+        x / (x + y)
+    }
+}
+
+impl Distribution<f64> for Beta {
+    fn quantile(&self, p: f64) -> f64 {
+        assert!(0.0 <= p && p <= 1.0);
+        if p == 0.0 {
+            return 0.0;
+        } else if p == 1.0 {
+            return 1.0;
+        }
+        special::invert_cdf(p, 0.0, 1.0, self.a / (self.a + self.b),
+                            |x| self.cumulative(x), |x| self.density(x))
+    }
+
+    fn cumulative(&self, sample: f64) -> f64 {
+        special::beta_inc(self.a, self.b, sample)
+    }
+
+    fn density(&self, sample: f64) -> f64 {
+        if 0.0 < sample && sample < 1.0 {
+            num::exp((self.a - 1.0) * num::ln(sample) + (self.b - 1.0) * (-sample).ln_1p()
+                     - special::ln_beta(self.a, self.b))
+        } else {
+            0.0
+        }
+    }
+}
+
+/// The Dirichlet distribution `Dir(alpha)`, a distribution over the
+/// `K - 1`-simplex (vectors of `K` non-negative numbers that sum to
+/// 1), where `K` is the length of `alpha`. This is the multivariate
+/// generalisation of `Beta`: `Dir([a, b])` gives `[x, 1 - x]` with `x`
+/// distributed as `Beta(a, b)`.
+///
+/// This is sampled by normalising a vector of independent `Gamma(alpha[i],
+/// 1)` random variables.
+pub struct Dirichlet {
+    priv gammas: ~[Gamma]
+}
+
+impl Dirichlet {
+    /// Construct a `Dirichlet` with concentration parameters
+    /// `alpha`. Fails if there are fewer than two parameters, or any
+    /// are not positive and finite.
+    pub fn new(alpha: &[f64]) -> Dirichlet {
+        unwrap_params("Dirichlet", Dirichlet::try_new(alpha))
+    }
+
+    /// Construct a `Dirichlet` with concentration parameters `alpha`,
+    /// returning an error if there are fewer than two parameters, or
+    /// any are not positive and finite.
+    pub fn try_new(alpha: &[f64]) -> Result<Dirichlet, ParameterError> {
+        if alpha.len() < 2 {
+            Err(ParameterError::new("alpha", "must have at least two elements"))
+        } else if !alpha.iter().all(|&a| is_positive(a)) {
+            Err(ParameterError::new("alpha", "must have positive and finite elements"))
+        } else {
+            Ok(Dirichlet {
+                gammas: alpha.iter().map(|&a| Gamma::new(a, 1.0)).collect()
+            })
+        }
+    }
+}
+
+impl Sample<~[f64]> for Dirichlet {
+    fn sample<R: Rng>(&self, rng: &mut R) -> ~[f64] {
+        let mut sum = 0.0;
+        let mut v: ~[f64] = self.gammas.iter().map(|g| {
+            let x = g.sample(rng);
+            sum += x;
+            x
+        }).collect();
+
+        for elem in v.mut_iter() {
+            *elem /= sum;
+        }
+        v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use distributions::{Sample, Distribution};
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() <= 1e-12 * expected.abs(),
+                "expected {}, got {}", expected, actual);
+    }
+
+    #[test]
+    fn test_beta() {
+        let mut rng = ::rng();
+        for &(a, b) in [(0.1, 0.1), (0.5, 2.0), (1.0, 1.0), (3.0, 20.0)].iter() {
+            let beta = Beta::new(a, b);
+            for _ in range(0, 1000) {
+                let x = beta.sample(&mut rng);
+                assert!(0.0 <= x && x <= 1.0);
+            }
+        }
+
+        // Beta(2, 3) has density 12 x (1 - x)^2.
+        let beta = Beta::new(2.0, 3.0);
+        assert_close(beta.cumulative(0.4), 0.5248);
+        assert_close(beta.density(0.4), 12.0 * 0.4 * 0.6 * 0.6);
+        assert!((beta.quantile(0.5248) - 0.4).abs() < 1e-12);
+        assert_eq!(beta.quantile(1.0), 1.0);
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_beta_invalid() {
+        Beta::new(0.0, 1.0);
+    }
+
+    #[test]
+    fn test_dirichlet() {
+        let mut rng = ::rng();
+        let dirichlet = Dirichlet::new([0.5, 1.0, 10.0]);
+        for _ in range(0, 1000) {
+            let v = dirichlet.sample(&mut rng);
+            assert_eq!(v.len(), 3);
+            assert!(v.iter().all(|&x| x >= 0.0));
+            let sum = v.iter().fold(0.0, |a, &b| a + b);
+            assert!((sum - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_dirichlet_invalid() {
+        Dirichlet::new([1.0, -1.0]);
+    }
+}
@@ -0,0 +1,335 @@
+use Rng;
+use super::{Sample, Distribution, special};
+use super::{ParameterError, unwrap_params};
+use std::num;
+
+/// The value of `n * min(p, 1 - p)` below which samples are generated
+/// by inversion, rather than by BTPE.
+static INVERSION_THRESHOLD: f64 = 30.0;
+
+/// The binomial distribution `Binomial(n, p)`, the distribution of the
+/// number of successes in `n` independent trials that each succeed
+/// with probability `p`.
+///
+/// When `n * p` is small this is sampled by sequential search of the
+/// cumulative distribution function (inversion), otherwise by the BTPE
+/// algorithm of V. Kachitvichyanukul and B. W. Schmeiser, [*Binomial
+/// Random Variate Generation*](http://dl.acm.org/citation.cfm?id=42381)
+/// (1988), which takes constant expected time.
+pub struct Binomial {
+    priv n: u64,
+    priv p: f64
+}
+
+impl Binomial {
+    /// Construct a `Binomial` with `n` trials, each with success
+    /// probability `p`. Fails if `p` is not in [0, 1].
+    pub fn new(n: u64, p: f64) -> Binomial {
+        unwrap_params("Binomial", Binomial::try_new(n, p))
+    }
+
+    /// Construct a `Binomial` with `n` trials, each with success
+    /// probability `p`, returning an error if `p` is not in [0, 1].
+    pub fn try_new(n: u64, p: f64) -> Result<Binomial, ParameterError> {
+        if 0.0 <= p && p <= 1.0 {
+            Ok(Binomial { n: n, p: p })
+        } else {
+            Err(ParameterError::new("p", "must be between 0 and 1"))
+        }
+    }
+}
+
+/// Sample `Binomial(n, p)` by inversion, for `p <= 1/2`.
+fn binomial_inversion<R: Rng>(rng: &mut R, n: u64, p: f64) -> u64 {
+    let q = 1.0 - p;
+    let n_f = n as f64;
+    let prob_zero = num::exp(n_f * num::ln(q));
+    let np = n_f * p;
+    // the search is restarted if it ever goes past this point, since
+    // it is only reached through rounding errors accumulating.
+    let bound = num::min(n_f, np + 10.0 * num::sqrt(np * q + 1.0));
+
+    let mut u = rng.gen::<f64>();
+    let mut x = 0;
+    let mut prob = prob_zero;
+    while u > prob {
+        x += 1;
+        if x as f64 > bound {
+            x = 0;
+            prob = prob_zero;
+            u = rng.gen::<f64>();
+        } else {
+            u -= prob;
+            prob *= (n_f - x as f64 + 1.0) * p / (x as f64 * q);
+        }
+    }
+    x
+}
+
+/// Sample `Binomial(n, p)` with the BTPE ("triangle, parallelogram,
+/// exponential") algorithm, for `p <= 1/2`.
+fn binomial_btpe<R: Rng>(rng: &mut R, n: u64, p: f64) -> u64 {
+    let n = n as f64;
+    let q = 1.0 - p;
+    let npq = n * p * q;
+    let f_m = n * p + p;
+    let m = f_m.floor();
+
+    // the areas of the regions of the majorising function: a triangle
+    // (p1), two parallelograms (p2) and two exponential tails (p3,
+    // p4).
+    let p1 = (2.195 * num::sqrt(npq) - 4.6 * q).floor() + 0.5;
+    let x_m = m + 0.5;
+    let x_l = x_m - p1;
+    let x_r = x_m + p1;
+    let c = 0.134 + 20.5 / (15.3 + m);
+    let lambda_l = {
+        let a = (f_m - x_l) / (f_m - x_l * p);
+        a * (1.0 + 0.5 * a)
+    };
+    let lambda_r = {
+        let a = (x_r - f_m) / (x_r * q);
+        a * (1.0 + 0.5 * a)
+    };
+    let p2 = p1 * (1.0 + 2.0 * c);
+    let p3 = p2 + c / lambda_l;
+    let p4 = p3 + c / lambda_r;
+
+    loop {
+        let u = rng.gen::<f64>() * p4;
+        let mut v = rng.gen::<f64>();
+
+        let y = if u <= p1 {
+            // the triangular region, which is always accepted.
+            return (x_m - p1 * v + u).floor() as u64;
+        } else if u <= p2 {
+            let x = x_l + (u - p1) / c;
+            v = v * c + 1.0 - (m - x + 0.5).abs() / p1;
+            if v > 1.0 {
+                continue;
+            }
+            x.floor()
+        } else if u <= p3 {
+            let y = (x_l + num::ln(v) / lambda_l).floor();
+            if y < 0.0 {
+                continue;
+            }
+            v *= (u - p2) * lambda_l;
+            y
+        } else {
+            let y = (x_r - num::ln(v) / lambda_r).floor();
+            if y > n {
+                continue;
+            }
+            v *= (u - p3) * lambda_r;
+            y
+        };
+
+        let k = (y - m).abs();
+        if k <= 20.0 || k >= 0.5 * npq - 1.0 {
+            // evaluate f(y)/f(m) explicitly, by the recurrence for the
+            // ratio of consecutive probabilities.
+            let s = p / q;
+            let a = s * (n + 1.0);
+            let mut f = 1.0;
+            let mut i = num::min(m, y) + 1.0;
+            while i <= num::max(m, y) {
+                f *= a / i - s;
+                i += 1.0;
+            }
+            if (m < y && v <= f) || (m >= y && v * f <= 1.0) {
+                return y as u64;
+            }
+            continue;
+        }
+
+        // squeeze using upper and lower bounds on ln(f(y)/f(m)).
+        let rho = (k / npq) * ((k * (k / 3.0 + 0.625) + 1.0 / 6.0) / npq + 0.5);
+        let t = -k * k / (2.0 * npq);
+        let ln_v = num::ln(v);
+        if ln_v < t - rho {
+            return y as u64;
+        }
+        if ln_v > t + rho {
+            continue;
+        }
+
+        // the final acceptance test, using Stirling's formula.
+        fn stirling_correction(x: f64) -> f64 {
+            let x2 = x * x;
+            (13860.0 - (462.0 - (132.0 - (99.0 - 140.0 / x2) / x2) / x2) / x2) / x / 166320.0
+        }
+        let x1 = y + 1.0;
+        let f1 = m + 1.0;
+        let z = n + 1.0 - m;
+        let w = n - y + 1.0;
+        let bound = x_m * num::ln(f1 / x1) + (n - m + 0.5) * num::ln(z / w) +
+            (y - m) * num::ln(w * p / (x1 * q)) +
+            stirling_correction(f1) + stirling_correction(z) -
+            stirling_correction(x1) - stirling_correction(w);
+        if ln_v <= bound {
+            return y as u64;
+        }
+    }
+}
+
+impl Sample<u64> for Binomial {
+    fn sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        if self.p == 0.0 {
+            return 0;
+        } else if self.p == 1.0 {
+            return self.n;
+        }
+
+        // the algorithms are only valid for p <= 1/2, so use the
+        // symmetry of swapping successes and failures.
+        let flipped = self.p > 0.5;
+        let p = if flipped { 1.0 - self.p } else { self.p };
+
+        let successes = if self.n as f64 * p < INVERSION_THRESHOLD {
+            binomial_inversion(rng, self.n, p)
+        } else {
+            binomial_btpe(rng, self.n, p)
+        };
+
+        if flipped { self.n - successes } else { successes }
+    }
+}
+
+impl Distribution<u64> for Binomial {
+    fn quantile(&self, p: f64) -> u64 {
+        assert!(0.0 <= p && p <= 1.0);
+        if p == 0.0 {
+            return 0;
+        } else if p == 1.0 {
+            return self.n;
+        }
+
+        // start from the normal approximation, and then step to the
+        // exact answer.
+        let n = self.n as f64;
+        let sd = num::sqrt(n * self.p * (1.0 - self.p));
+        let guess = n * self.p + sd * special::std_normal_quantile(p);
+        let guess = if guess <= 0.0 {
+            0
+        } else if guess >= n {
+            self.n
+        } else {
+            guess.floor() as u64
+        };
+        special::search_discrete_quantile(p, guess, |k| self.cumulative(k))
+    }
+
+    fn cumulative(&self, sample: u64) -> f64 {
+        if sample >= self.n {
+            1.0
+        } else {
+            let k = sample as f64;
+            special::beta_inc(self.n as f64 - k, k + 1.0, 1.0 - self.p)
+        }
+    }
+
+    fn density(&self, sample: u64) -> f64 {
+        if sample > self.n {
+            return 0.0;
+        } else if self.p == 0.0 || self.p == 1.0 {
+            // all of the mass is at one end.
+            let certain = if self.p == 0.0 { 0 } else { self.n };
+            return if sample == certain { 1.0 } else { 0.0 };
+        }
+
+        let n = self.n as f64;
+        let k = sample as f64;
+        num::exp(special::ln_choose(n, k) + k * num::ln(self.p) + (n - k) * (-self.p).ln_1p())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use distributions::{Sample, Distribution, ChiSquared};
+    use std::{num, vec};
+
+    #[test]
+    fn test_binomial_sample() {
+        let mut rng = ::rng();
+        for &(n, p) in [(0u64, 0.3), (1, 0.5), (100, 0.1), (100, 0.9),
+                        (1000, 0.5), (10_000_000, 0.01), (20, 0.0), (20, 1.0)].iter() {
+            let binomial = Binomial::new(n, p);
+            let mut sum = 0;
+            for _ in range(0, 1000) {
+                let x = binomial.sample(&mut rng);
+                assert!(x <= n);
+                sum += x;
+            }
+            let mean = sum as f64 / 1000.0;
+            let expected = n as f64 * p;
+            let sd = num::sqrt(n as f64 * p * (1.0 - p) / 1000.0);
+            assert!((mean - expected).abs() <= 6.0 * sd,
+                    "n = {}, p = {}, mean = {}", n, p, mean);
+        }
+    }
+
+    #[test]
+    fn test_binomial_btpe_pmf() {
+        // n * p is far above the inversion threshold, so this uses
+        // BTPE. Compare the sample counts of each value near the mean,
+        // and of the two tails, to the density with a chi-squared test.
+        let mut rng = ::rng();
+        let binomial = Binomial::new(1000, 0.4);
+        let (low, high) = (355u64, 445u64);
+        let samples = 20000;
+        let bins = (high - low + 3) as uint;
+        let mut counts = vec::from_elem(bins, 0u);
+        for _ in range(0, samples) {
+            let x = binomial.sample(&mut rng);
+            let bin = if x < low { 0 } else if x > high { bins - 1 } else { (x - low) as uint + 1 };
+            counts[bin] += 1;
+        }
+
+        let mut probs = ~[binomial.cumulative(low - 1)];
+        for k in range(low, high + 1) {
+            probs.push(binomial.density(k));
+        }
+        probs.push(1.0 - binomial.cumulative(high));
+
+        let mut stat = 0.0;
+        for (&count, &prob) in counts.iter().zip(probs.iter()) {
+            let expected = samples as f64 * prob;
+            let d = count as f64 - expected;
+            stat += d * d / expected;
+        }
+        let p_value = 1.0 - ChiSquared::new((bins - 1) as f64).cumulative(stat);
+        assert!(p_value > 1e-6, "chi-squared = {}, p = {}", stat, p_value);
+    }
+
+    #[test]
+    fn test_binomial_distribution() {
+        // Binomial(4, 1/2) has probabilities [1, 4, 6, 4, 1] / 16.
+        let binomial = Binomial::new(4, 0.5);
+        let probs = [1.0, 4.0, 6.0, 4.0, 1.0];
+        let mut cumulative = 0.0;
+        for (k, &prob) in probs.iter().enumerate() {
+            cumulative += prob / 16.0;
+            assert!((binomial.density(k as u64) - prob / 16.0).abs() < 1e-15);
+            assert!((binomial.cumulative(k as u64) - cumulative).abs() < 1e-15);
+        }
+        assert_eq!(binomial.density(5), 0.0);
+        assert_eq!(binomial.quantile(0.5), 2);
+        assert_eq!(binomial.quantile(1.0), 4);
+
+        let binomial = Binomial::new(1000, 0.3);
+        for &p in [0.001, 0.2, 0.5, 0.999].iter() {
+            let k = binomial.quantile(p);
+            assert!(binomial.cumulative(k) >= p);
+            assert!(k == 0 || binomial.cumulative(k - 1) < p);
+        }
+    }
+
+    #[test]
+    fn test_binomial_try_new() {
+        assert!(Binomial::try_new(10, -0.1).is_err());
+        assert!(Binomial::try_new(10, 1.5).is_err());
+        assert!(Binomial::try_new(10, Float::NaN()).is_err());
+    }
+}
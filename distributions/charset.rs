@@ -0,0 +1,227 @@
+use Rng;
+use super::Sample;
+use super::{ParameterError, unwrap_params};
+use super::uniform::Uniform;
+use std::str;
+
+// the surrogate code points, which are not valid `char`s.
+static SURROGATE_LOW: u32 = 0xD800;
+static SURROGATE_COUNT: u32 = 0x800;
+
+enum CharsetKind {
+    // an explicit list of characters.
+    Alphabet(~[char]),
+    // the scalar values from this one upwards, skipping the
+    // surrogates.
+    ScalarRange(u32)
+}
+
+/// A distribution choosing a `char` uniformly from a set of
+/// characters: either the characters of a string, or a range of
+/// Unicode scalar values.
+///
+/// # Example
+///
+/// ~~~ {.rust}
+/// use rand::distributions::{CharsetDist, Sample};
+///
+/// fn main() {
+///     let hex = CharsetDist::new("0123456789abcdef");
+///     let mut rng = rand::task_rng();
+///     println!("{}", hex.sample(rng));
+/// }
+/// ~~~
+pub struct CharsetDist {
+    priv kind: CharsetKind,
+    priv index: Uniform<uint>
+}
+
+impl CharsetDist {
+    /// Construct a `CharsetDist` choosing uniformly from the
+    /// characters of `alphabet`; a character that appears more than
+    /// once is chosen proportionally more often. Fails if `alphabet`
+    /// is empty.
+    pub fn new(alphabet: &str) -> CharsetDist {
+        unwrap_params("CharsetDist", CharsetDist::try_new(alphabet))
+    }
+
+    /// Construct a `CharsetDist` choosing uniformly from the
+    /// characters of `alphabet`, returning an error if `alphabet` is
+    /// empty.
+    pub fn try_new(alphabet: &str) -> Result<CharsetDist, ParameterError> {
+        let chars: ~[char] = alphabet.iter().collect();
+        match Uniform::try_new(0, chars.len()) {
+            Ok(index) => Ok(CharsetDist { kind: Alphabet(chars), index: index }),
+            Err(_) => Err(ParameterError::new("alphabet", "must be non-empty"))
+        }
+    }
+
+    /// Construct a `CharsetDist` choosing uniformly from the Unicode
+    /// scalar values in [`low`, `high`]; the surrogate code points
+    /// are never generated. Fails if `low > high`.
+    pub fn new_range(low: char, high: char) -> CharsetDist {
+        unwrap_params("CharsetDist", CharsetDist::try_new_range(low, high))
+    }
+
+    /// Construct a `CharsetDist` choosing uniformly from the Unicode
+    /// scalar values in [`low`, `high`], returning an error if `low >
+    /// high`.
+    pub fn try_new_range(low: char, high: char) -> Result<CharsetDist, ParameterError> {
+        let (low, high) = (low as u32, high as u32);
+        if low > high {
+            return Err(ParameterError::new("high", "must be at least low"));
+        }
+        // neither end can be a surrogate, so the range either contains
+        // all of them or none.
+        let mut count = high - low + 1;
+        if low < SURROGATE_LOW && high >= SURROGATE_LOW {
+            count -= SURROGATE_COUNT;
+        }
+        Ok(CharsetDist {
+            kind: ScalarRange(low),
+            index: Uniform::new(0, count as uint)
+        })
+    }
+
+    /// The ASCII letters and digits, as used by `Rng::gen_ascii_str`.
+    pub fn alphanumeric() -> CharsetDist {
+        CharsetDist::new("ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789")
+    }
+}
+
+impl Sample<char> for CharsetDist {
+    fn sample<R: Rng>(&self, rng: &mut R) -> char {
+        let i = self.index.sample(rng);
+        match self.kind {
+            Alphabet(ref chars) => chars[i],
+            ScalarRange(low) => {
+                let mut c = low + i as u32;
+                if low < SURROGATE_LOW && c >= SURROGATE_LOW {
+                    c += SURROGATE_COUNT;
+                }
+                c as char
+            }
+        }
+    }
+}
+
+/// A distribution over strings, with a length drawn from `len` and
+/// each character drawn independently from `charset`. This is the
+/// string analogue of `ListDist`.
+///
+/// # Example
+///
+/// ~~~ {.rust}
+/// use rand::distributions::{StringDist, CharsetDist, Uniform, Sample};
+///
+/// fn main() {
+///     // identifiers of 8 to 12 lowercase letters.
+///     let ids = StringDist::new(Uniform::new_inclusive(8u, 12),
+///                               CharsetDist::new_range('a', 'z'));
+///     let mut rng = rand::task_rng();
+///     println!("{}", ids.sample(rng));
+/// }
+/// ~~~
+pub struct StringDist<I> {
+    priv len: I,
+    priv charset: CharsetDist
+}
+
+impl<I: Sample<uint>> StringDist<I> {
+    /// Construct a `StringDist` with lengths drawn from `len` and
+    /// characters from `charset`. For a fixed length `n`, use
+    /// `Uniform::new_inclusive(n, n)`.
+    pub fn new(len: I, charset: CharsetDist) -> StringDist<I> {
+        StringDist { len: len, charset: charset }
+    }
+}
+
+impl<I: Sample<uint>> Sample<~str> for StringDist<I> {
+    fn sample<R: Rng>(&self, rng: &mut R) -> ~str {
+        let len = self.len.sample(rng);
+        let mut s = str::with_capacity(len);
+        for _ in range(0, len) {
+            s.push_char(self.charset.sample(rng));
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use distributions::{Sample, Uniform};
+
+    #[test]
+    fn test_alphabet() {
+        let mut rng = ::rng();
+        let dist = CharsetDist::new("aβ€𝄞");
+        let mut seen = [false, .. 4];
+        for _ in range(0, 1000) {
+            match dist.sample(&mut rng) {
+                'a' => seen[0] = true,
+                'β' => seen[1] = true,
+                '€' => seen[2] = true,
+                '𝄞' => seen[3] = true,
+                c => fail!("unexpected char {}", c)
+            }
+        }
+        assert!(seen.iter().all(|&x| x));
+
+        let dist = CharsetDist::alphanumeric();
+        for _ in range(0, 1000) {
+            assert!(dist.sample(&mut rng).is_alphanumeric());
+        }
+    }
+
+    #[test]
+    fn test_range() {
+        let mut rng = ::rng();
+        let dist = CharsetDist::new_range('a', 'z');
+        for _ in range(0, 1000) {
+            let c = dist.sample(&mut rng);
+            assert!('a' <= c && c <= 'z');
+        }
+
+        // the only scalar values either side of the surrogates.
+        let dist = CharsetDist::new_range('\uD7FF', '\uE000');
+        let mut seen = [false, .. 2];
+        for _ in range(0, 100) {
+            match dist.sample(&mut rng) {
+                '\uD7FF' => seen[0] = true,
+                '\uE000' => seen[1] = true,
+                c => fail!("unexpected char {}", c as u32)
+            }
+        }
+        assert_eq!(seen.to_owned(), ~[true, true]);
+
+        let dist = CharsetDist::new_range('\x00', '\U0010FFFF');
+        for _ in range(0, 1000) {
+            let c = dist.sample(&mut rng) as u32;
+            assert!(c < 0xD800 || (0xDFFF < c && c <= 0x10FFFF));
+        }
+    }
+
+    #[test]
+    fn test_try_new() {
+        assert!(CharsetDist::try_new("").is_err());
+        assert!(CharsetDist::try_new("x").is_ok());
+        assert!(CharsetDist::try_new_range('b', 'a').is_err());
+        assert!(CharsetDist::try_new_range('a', 'a').is_ok());
+    }
+
+    #[test]
+    fn test_string_dist() {
+        let mut rng = ::rng();
+        let dist = StringDist::new(Uniform::new_inclusive(3u, 6), CharsetDist::new("xyz"));
+        for _ in range(0, 100) {
+            let s = dist.sample(&mut rng);
+            let len = s.char_len();
+            assert!(3 <= len && len <= 6);
+            assert!(s.iter().all(|c| c == 'x' || c == 'y' || c == 'z'));
+        }
+
+        let empty = StringDist::new(Uniform::new_inclusive(0u, 0), CharsetDist::new("x"));
+        assert_eq!(empty.sample(&mut rng), ~"");
+    }
+}
@@ -1,19 +1,33 @@
 use Rand;
 use Rng;
 use super::{ziggurat_tables, Sample, Distribution};
+use super::{ParameterError, is_positive, unwrap_params};
 use super::ziggurat_tables::ziggurat;
 use std::num;
 
+/// The exponential distribution `Exp(rate)`.
 pub struct Exp {
-    rate: f64
+    priv rate: f64
 }
 impl Exp {
-    fn new(rate: f64) -> Exp {
-        assert!(rate > 0.0);
-        Exp { rate: rate }
+    /// Construct an `Exp` with the given rate (the reciprocal of the
+    /// mean). Fails if `rate` is not positive and finite.
+    pub fn new(rate: f64) -> Exp {
+        unwrap_params("Exp", Exp::try_new(rate))
     }
 
-    fn standard() -> Exp {
+    /// Construct an `Exp` with the given rate, returning an error if
+    /// `rate` is not positive and finite.
+    pub fn try_new(rate: f64) -> Result<Exp, ParameterError> {
+        if is_positive(rate) {
+            Ok(Exp { rate: rate })
+        } else {
+            Err(ParameterError::not_positive("rate"))
+        }
+    }
+
+    /// Construct the standard exponential distribution, `Exp(1)`.
+    pub fn standard() -> Exp {
         Exp { rate: 1.0 }
     }
 }
@@ -91,6 +105,26 @@ mod tests {
     use extra::test::BenchHarness;
     use super::*;
     use rng;
+    use distributions::ParameterError;
+
+    #[test]
+    fn test_exp_try_new() {
+        assert!(Exp::try_new(2.0).is_ok());
+        assert!(Exp::try_new(Float::infinity()).is_err());
+        match Exp::try_new(0.0) {
+            Err(e) => {
+                assert_eq!(e, ParameterError::not_positive("rate"));
+                assert_eq!(e.to_str(), ~"invalid parameter `rate`: must be positive and finite");
+            }
+            Ok(_) => fail!("zero rate accepted")
+        }
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_exp_invalid_rate() {
+        Exp::new(-1.0);
+    }
 
     #[bench]
     fn exp1(b: &mut BenchHarness) {
@@ -1,37 +1,156 @@
 use Rng;
-use super::{Sample, Distribution};
+use super::{Sample, Distribution, special};
+use super::{ParameterError, is_positive, unwrap_params};
+use super::normal::StandardNormal;
+use super::exp::Exp1;
 use std::num;
-struct Gamma {
-    shape: f64,
-    rate: f64,
+
+/// The Gamma distribution `Gamma(shape, rate)`, with density
+/// proportional to `x^(shape - 1) e^(-rate x)` for `x > 0`.
+///
+/// Sampling uses the algorithm of G. Marsaglia and W. W. Tsang, [*A
+/// Simple Method for Generating Gamma
+/// Variables*](http://dl.acm.org/citation.cfm?id=358414) (2000), for
+/// `shape >= 1`, and boosts it with `Gamma(shape + 1) * U^(1/shape)`
+/// (`U` uniform on [0, 1)) for `shape < 1`. When `shape == 1` this is
+/// just an exponential distribution.
+pub struct Gamma {
+    priv shape: f64,
+    priv rate: f64,
+    priv repr: GammaRepr,
+}
+
+enum GammaRepr {
+    Large(GammaLargeShape),
+    One,
+    Small(GammaSmallShape)
+}
+
+/// Samples from `Gamma(shape, 1)` for `shape < 1`.
+struct GammaSmallShape {
+    inv_shape: f64,
+    large_shape: GammaLargeShape
+}
+
+/// Samples from `Gamma(shape, 1)` for `shape >= 1`.
+struct GammaLargeShape {
+    d: f64,
+    c: f64
 }
 
 impl Gamma {
+    /// Construct a `Gamma` with the given shape and rate parameters
+    /// (the rate is the reciprocal of the scale). Fails if either is
+    /// not positive and finite.
     pub fn new(shape: f64, rate: f64) -> Gamma {
-        assert!(shape > 0.0);
-        assert!(rate > 0.0);
-        Gamma {
+        unwrap_params("Gamma", Gamma::try_new(shape, rate))
+    }
+
+    /// Construct a `Gamma` with the given shape and rate parameters,
+    /// returning an error if either is not positive and finite.
+    pub fn try_new(shape: f64, rate: f64) -> Result<Gamma, ParameterError> {
+        if !is_positive(shape) {
+            return Err(ParameterError::not_positive("shape"));
+        } else if !is_positive(rate) {
+            return Err(ParameterError::not_positive("rate"));
+        }
+
+        let repr = if shape == 1.0 {
+            One
+        } else if shape < 1.0 {
+            Small(GammaSmallShape::new(shape))
+        } else {
+            Large(GammaLargeShape::new(shape))
+        };
+
+        Ok(Gamma {
             shape: shape,
-            rate: rate
+            rate: rate,
+            repr: repr
+        })
+    }
+}
+
+impl GammaSmallShape {
+    fn new(shape: f64) -> GammaSmallShape {
+        GammaSmallShape {
+            inv_shape: 1.0 / shape,
+            large_shape: GammaLargeShape::new(shape + 1.0)
+        }
+    }
+}
+
+impl GammaLargeShape {
+    fn new(shape: f64) -> GammaLargeShape {
+        let d = shape - 1.0 / 3.0;
+        GammaLargeShape {
+            d: d,
+            c: 1.0 / num::sqrt(9.0 * d)
+        }
+    }
+}
+
+impl Sample<f64> for GammaSmallShape {
+    fn sample<R: Rng>(&self, rng: &mut R) -> f64 {
+        let u = rng.gen::<f64>();
+        self.large_shape.sample(rng) * u.pow(&self.inv_shape)
+    }
+}
+
+impl Sample<f64> for GammaLargeShape {
+    fn sample<R: Rng>(&self, rng: &mut R) -> f64 {
+        loop {
+            let x = *rng.gen::<StandardNormal>();
+            let v_cbrt = 1.0 + self.c * x;
+            if v_cbrt <= 0.0 { // a^3 <= 0 iff a <= 0
+                continue
+            }
+
+            let v = v_cbrt * v_cbrt * v_cbrt;
+            let u = rng.gen::<f64>();
+
+            let x_sqr = x * x;
+            if u < 1.0 - 0.0331 * x_sqr * x_sqr ||
+                num::ln(u) < 0.5 * x_sqr + self.d * (1.0 - v + num::ln(v)) {
+                return self.d * v;
+            }
         }
     }
 }
 
 impl Sample<f64> for Gamma {
-    fn sample<R: Rng>(&self, _rng: &mut R) -> f64 {
-        fail!()
+    fn sample<R: Rng>(&self, rng: &mut R) -> f64 {
+        let standard = match self.repr {
+            Small(ref g) => g.sample(rng),
+            One => *rng.gen::<Exp1>(),
+            Large(ref g) => g.sample(rng),
+        };
+        standard / self.rate
     }
 }
 
 impl Distribution<f64> for Gamma {
     fn quantile(&self, p: f64) -> f64 {
         assert!(0.0 <= p && p <= 1.0);
-        fail!("unimplemented")
+        if p == 0.0 {
+            return 0.0;
+        } else if p == 1.0 {
+            return Float::infinity();
+        }
+
+        // bracket the quantile, starting from the mean.
+        let mean = self.shape / self.rate;
+        let mut high = mean;
+        while self.cumulative(high) < p {
+            high *= 2.0;
+        }
+        special::invert_cdf(p, 0.0, high, mean,
+                            |x| self.cumulative(x), |x| self.density(x))
     }
 
     fn cumulative(&self, sample: f64) -> f64 {
         if sample > 0.0 {
-            fail!("unimplemented")
+            special::gamma_p(self.shape, self.rate * sample)
         } else {
             0.0
         }
@@ -40,65 +159,357 @@ impl Distribution<f64> for Gamma {
     fn density(&self, sample: f64) -> f64 {
         if sample > 0.0 {
             let (_, shape_log_gamma) = self.shape.lgamma();
+            let x = self.rate * sample;
             self.rate *
-                (sample * self.rate).pow(&self.shape) *
-                num::exp(-self.rate * sample - shape_log_gamma)
+                num::exp((self.shape - 1.0) * num::ln(x) - x - shape_log_gamma)
         } else {
             0.0
         }
     }
 }
 
-impl Gamma {
-    pub fn best_gamma<R: Rng>(&self, r: &mut R, v: &mut [f64]) {
-        let a = self.shape;
-        assert!(0.0 < a && a < 1.0);
-        let a_inv = 1.0 / a;
-        let z = 0.07 + 0.75 * num::sqrt(1.0 - a);
-        let z_inv = 1.0 / z;
-        let z_on_a = z * a_inv;
-        let b = 1.0 - num::exp(-z) * a / z;
-
-        for elem in v.mut_iter() {
-            loop {
-                let p = b * r.gen::<f64>();
-                if p <= 1.0 {
-                    let x = z * p.pow(&a_inv);
-                    if r.gen::<f64>() <= (2.0 - x) / (2.0 + x) {
-                        *elem = x;
-                        break;
-                    }
-                } else {
-                    let x = -num::ln(z_on_a * (b - p));
-                    let y = x * z_inv;
-                    let u = r.gen::<f64>();
-                    if u * (a + y - a * y) < 1.0 || u < y.pow(&(a - 1.0)) {
-                        *elem = x;
-                        break;
-                    }
-                }
-            }
+/// The chi-squared distribution `χ²(k)`, where `k` is the degrees of
+/// freedom. This is the distribution of the sum of the squares of `k`
+/// independent standard normal random variables, and is equal to
+/// `Gamma(k/2, 1/2)`.
+pub struct ChiSquared {
+    priv k: f64,
+    priv gamma: Gamma
+}
+
+impl ChiSquared {
+    /// Construct a `ChiSquared` with `k` degrees of freedom. Fails if
+    /// `k` is not positive and finite.
+    pub fn new(k: f64) -> ChiSquared {
+        unwrap_params("ChiSquared", ChiSquared::try_new(k))
+    }
+
+    /// Construct a `ChiSquared` with `k` degrees of freedom, returning
+    /// an error if `k` is not positive and finite.
+    pub fn try_new(k: f64) -> Result<ChiSquared, ParameterError> {
+        if is_positive(k) {
+            Ok(ChiSquared { k: k, gamma: Gamma::new(0.5 * k, 0.5) })
+        } else {
+            Err(ParameterError::not_positive("k"))
+        }
+    }
+}
+
+impl Sample<f64> for ChiSquared {
+    fn sample<R: Rng>(&self, rng: &mut R) -> f64 {
+        if self.k == 1.0 {
+            // squaring a normal is much cheaper than a Gamma(1/2) sample.
+            let norm = *rng.gen::<StandardNormal>();
+            norm * norm
+        } else {
+            self.gamma.sample(rng)
+        }
+    }
+}
+
+impl Distribution<f64> for ChiSquared {
+    fn quantile(&self, p: f64) -> f64 {
+        self.gamma.quantile(p)
+    }
+    fn cumulative(&self, sample: f64) -> f64 {
+        self.gamma.cumulative(sample)
+    }
+    fn density(&self, sample: f64) -> f64 {
+        self.gamma.density(sample)
+    }
+}
+
+/// The Fisher F-distribution `F(m, n)`, the distribution of the ratio
+/// `(X/m) / (Y/n)` where `X` and `Y` are independent `χ²(m)` and
+/// `χ²(n)` random variables.
+pub struct FisherF {
+    priv m: f64,
+    priv n: f64,
+    priv numer: ChiSquared,
+    priv denom: ChiSquared,
+    // the distribution of `(X/m) / (Y/n)` is the same as `X/Y * n/m`
+    priv dof_ratio: f64
+}
+
+impl FisherF {
+    /// Construct a `FisherF` with `m` and `n` degrees of freedom. Fails
+    /// if either is not positive and finite.
+    pub fn new(m: f64, n: f64) -> FisherF {
+        unwrap_params("FisherF", FisherF::try_new(m, n))
+    }
+
+    /// Construct a `FisherF` with `m` and `n` degrees of freedom,
+    /// returning an error if either is not positive and finite.
+    pub fn try_new(m: f64, n: f64) -> Result<FisherF, ParameterError> {
+        if !is_positive(m) {
+            Err(ParameterError::not_positive("m"))
+        } else if !is_positive(n) {
+            Err(ParameterError::not_positive("n"))
+        } else {
+            Ok(FisherF {
+                m: m,
+                n: n,
+                numer: ChiSquared::new(m),
+                denom: ChiSquared::new(n),
+                dof_ratio: n / m
+            })
+        }
+    }
+}
+
+impl Sample<f64> for FisherF {
+    fn sample<R: Rng>(&self, rng: &mut R) -> f64 {
+        self.numer.sample(rng) / self.denom.sample(rng) * self.dof_ratio
+    }
+}
+
+impl Distribution<f64> for FisherF {
+    fn quantile(&self, p: f64) -> f64 {
+        assert!(0.0 <= p && p <= 1.0);
+        if p == 0.0 {
+            return 0.0;
+        } else if p == 1.0 {
+            return Float::infinity();
+        }
+
+        let mut high = 1.0;
+        while self.cumulative(high) < p {
+            high *= 2.0;
+        }
+        special::invert_cdf(p, 0.0, high, 0.5 * high,
+                            |x| self.cumulative(x), |x| self.density(x))
+    }
+
+    fn cumulative(&self, sample: f64) -> f64 {
+        if sample > 0.0 {
+            let mx = self.m * sample;
+            special::beta_inc(0.5 * self.m, 0.5 * self.n, mx / (mx + self.n))
+        } else {
+            0.0
+        }
+    }
+
+    fn density(&self, sample: f64) -> f64 {
+        if sample > 0.0 {
+            let (m, n) = (self.m, self.n);
+            let mx = m * sample;
+            num::exp(0.5 * (m * num::ln(mx) + n * num::ln(n) - (m + n) * num::ln(mx + n))
+                     - num::ln(sample) - special::ln_beta(0.5 * m, 0.5 * n))
+        } else {
+            0.0
         }
     }
 }
 
+/// Student's t-distribution `t(n)`, where `n` is the degrees of
+/// freedom. This is the distribution of `Z / sqrt(V/n)`, where `Z` is
+/// a standard normal and `V` an independent `χ²(n)` random variable.
+pub struct StudentT {
+    priv n: f64,
+    priv chi: ChiSquared
+}
+
+impl StudentT {
+    /// Construct a `StudentT` with `n` degrees of freedom. Fails if `n`
+    /// is not positive and finite.
+    pub fn new(n: f64) -> StudentT {
+        unwrap_params("StudentT", StudentT::try_new(n))
+    }
+
+    /// Construct a `StudentT` with `n` degrees of freedom, returning an
+    /// error if `n` is not positive and finite.
+    pub fn try_new(n: f64) -> Result<StudentT, ParameterError> {
+        if is_positive(n) {
+            Ok(StudentT { n: n, chi: ChiSquared::new(n) })
+        } else {
+            Err(ParameterError::not_positive("n"))
+        }
+    }
+}
+
+impl Sample<f64> for StudentT {
+    fn sample<R: Rng>(&self, rng: &mut R) -> f64 {
+        let norm = *rng.gen::<StandardNormal>();
+        norm * num::sqrt(self.n / self.chi.sample(rng))
+    }
+}
+
+impl Distribution<f64> for StudentT {
+    fn quantile(&self, p: f64) -> f64 {
+        assert!(0.0 <= p && p <= 1.0);
+        if p == 0.0 {
+            return Float::neg_infinity();
+        } else if p == 1.0 {
+            return Float::infinity();
+        } else if p < 0.5 {
+            // the distribution is symmetric about 0.
+            return -self.quantile(1.0 - p);
+        } else if p == 0.5 {
+            return 0.0;
+        }
+
+        let mut high = 1.0;
+        while self.cumulative(high) < p {
+            high *= 2.0;
+        }
+        special::invert_cdf(p, 0.0, high, 0.5 * high,
+                            |x| self.cumulative(x), |x| self.density(x))
+    }
+
+    fn cumulative(&self, sample: f64) -> f64 {
+        let n = self.n;
+        let tail = 0.5 * special::beta_inc(0.5 * n, 0.5, n / (n + sample * sample));
+        if sample > 0.0 { 1.0 - tail } else { tail }
+    }
+
+    fn density(&self, sample: f64) -> f64 {
+        let n = self.n;
+        let ln_norm = -special::ln_beta(0.5 * n, 0.5) - 0.5 * num::ln(n);
+        num::exp(ln_norm - 0.5 * (n + 1.0) * (sample * sample / n).ln_1p())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rng::StdRng;
+    use distributions::{Sample, Distribution};
+    use std::num;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() <= 1e-12 * expected.abs(),
+                "expected {}, got {}", expected, actual);
+    }
+
+    #[test]
+    fn test_gamma_sample() {
+        let mut rng = ::rng();
+        for &shape in [0.1, 0.5, 1.0, 1.5, 10.0, 1000.0].iter() {
+            let gamma = Gamma::new(shape, 2.0);
+            for _ in range(0, 1000) {
+                let x = gamma.sample(&mut rng);
+                assert!(x >= 0.0 && x.is_finite());
+            }
+        }
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_gamma_invalid_shape() {
+        Gamma::new(0.0, 1.0);
+    }
+    #[test]
+    #[should_fail]
+    fn test_gamma_invalid_rate() {
+        Gamma::new(1.0, -1.0);
+    }
+
+    #[test]
+    fn test_gamma_cumulative() {
+        // Gamma(1, rate) is Exp(rate).
+        let exp = Gamma::new(1.0, 2.0);
+        assert_close(exp.cumulative(0.75), 1.0 - num::exp(-1.5));
+        assert_close(exp.density(0.75), 2.0 * num::exp(-1.5));
+
+        let gamma = Gamma::new(2.0, 1.0);
+        assert_close(gamma.cumulative(2.0), 1.0 - 3.0 * num::exp(-2.0));
+        assert_close(gamma.density(2.0), 2.0 * num::exp(-2.0));
+        assert_eq!(gamma.cumulative(-1.0), 0.0);
+    }
+
+    #[test]
+    fn test_gamma_quantile() {
+        for &shape in [0.3, 1.0, 4.5].iter() {
+            let gamma = Gamma::new(shape, 3.0);
+            for &p in [0.001, 0.1, 0.5, 0.9, 0.999].iter() {
+                let x = gamma.quantile(p);
+                assert!((gamma.cumulative(x) - p).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_chi_squared() {
+        let mut rng = ::rng();
+        for &k in [1.0, 2.0, 7.5].iter() {
+            let chi = ChiSquared::new(k);
+            for _ in range(0, 1000) {
+                assert!(chi.sample(&mut rng) >= 0.0);
+            }
+        }
+
+        // chi^2(2) is Exp(1/2).
+        let chi = ChiSquared::new(2.0);
+        assert_close(chi.cumulative(3.0), 1.0 - num::exp(-1.5));
+        assert_close(chi.density(3.0), 0.5 * num::exp(-1.5));
+        assert!((ChiSquared::new(1.0).quantile(0.95) - 3.841458820694124).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_student_t() {
+        let mut rng = ::rng();
+        let t = StudentT::new(3.0);
+        for _ in range(0, 1000) {
+            assert!(t.sample(&mut rng).is_finite());
+        }
+
+        // t(1) is the standard Cauchy distribution.
+        let cauchy = StudentT::new(1.0);
+        for &x in [-10.0, -1.0, -0.1, 0.3, 2.0, 50.0].iter() {
+            let expected = 0.5 + x.atan() / Real::pi();
+            assert!((cauchy.cumulative(x) - expected).abs() < 1e-13);
+            assert_close(cauchy.density(x), 1.0 / (Real::pi::<f64>() * (1.0 + x * x)));
+        }
+        assert_eq!(cauchy.cumulative(0.0), 0.5);
+        assert_eq!(cauchy.quantile(0.5), 0.0);
+        assert!((cauchy.quantile(0.75) - 1.0).abs() < 1e-12);
+        assert!((cauchy.quantile(0.25) + 1.0).abs() < 1e-12);
+        assert!((StudentT::new(10.0).quantile(0.975) - 2.228138851986273).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_fisher_f() {
+        let mut rng = ::rng();
+        let f = FisherF::new(2.0, 32.0);
+        for _ in range(0, 1000) {
+            assert!(f.sample(&mut rng) >= 0.0);
+        }
+
+        // F(2, 2) has CDF x / (1 + x).
+        let f = FisherF::new(2.0, 2.0);
+        assert_close(f.cumulative(3.0), 0.75);
+        assert_close(f.density(3.0), 1.0 / 16.0);
+        assert!((f.quantile(0.75) - 3.0).abs() < 1e-12);
+        assert_eq!(f.cumulative(0.0), 0.0);
+    }
+}
+
+#[cfg(test)]
+mod bench {
+    use super::*;
+    use distributions::Sample;
     use extra::test::BenchHarness;
-    use extra::stats::Stats;
 
     #[bench]
-    #[ignore]
-    fn bench_best(b: &mut BenchHarness) {
-        let gamma = Gamma::new(0.1, 2.0);
-        let mut rng: StdRng = ::Rng::new();
-        let mut vec = ~[0.0f64, .. 100];
+    fn bench_gamma_large_shape(b: &mut BenchHarness) {
+        let gamma = Gamma::new(10., 1.0);
+        let mut rng = ::rng();
 
         do b.iter {
-            gamma.best_gamma(&mut rng, vec);
+            for _ in range(0, 100) {
+                gamma.sample(&mut rng);
+            }
+        }
+    }
+
+    #[bench]
+    fn bench_gamma_small_shape(b: &mut BenchHarness) {
+        let gamma = Gamma::new(0.1, 1.0);
+        let mut rng = ::rng();
+
+        do b.iter {
+            for _ in range(0, 100) {
+                gamma.sample(&mut rng);
+            }
         }
-        println(fmt!("mean = %f, var = %f", vec.mean() as float, vec.var() as float));
     }
 }
@@ -0,0 +1,236 @@
+use Rng;
+use super::{Sample, Distribution, special};
+use super::{ParameterError, is_positive, unwrap_params};
+use super::exp::Exp1;
+use super::gamma::Gamma;
+use super::poisson::Poisson;
+use std::{num, u64};
+
+/// Is `p` a valid, non-zero probability of success?
+fn is_success_probability(p: f64) -> bool {
+    0.0 < p && p <= 1.0
+}
+
+/// The geometric distribution `Geometric(p)`, the distribution of the
+/// number of failures before the first success in a sequence of
+/// independent trials that each succeed with probability `p`.
+///
+/// This is sampled as `floor(E / -ln(1 - p))` for `E` an `Exp(1)`
+/// random variable, which takes constant time for any `p`.
+pub struct Geometric {
+    priv p: f64,
+    // ln(1 - p), which is -infinity when p == 1.
+    priv ln_q: f64
+}
+
+impl Geometric {
+    /// Construct a `Geometric` with success probability `p`. Fails if
+    /// `p` is not in (0, 1].
+    pub fn new(p: f64) -> Geometric {
+        unwrap_params("Geometric", Geometric::try_new(p))
+    }
+
+    /// Construct a `Geometric` with success probability `p`, returning
+    /// an error if `p` is not in (0, 1].
+    pub fn try_new(p: f64) -> Result<Geometric, ParameterError> {
+        if is_success_probability(p) {
+            Ok(Geometric { p: p, ln_q: (-p).ln_1p() })
+        } else {
+            Err(ParameterError::new("p", "must be in (0, 1]"))
+        }
+    }
+}
+
+impl Sample<u64> for Geometric {
+    fn sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        let x = (*rng.gen::<Exp1>() / -self.ln_q).floor();
+        // the distribution has an extremely long tail for tiny `p`.
+        if x >= u64::max_value as f64 { u64::max_value } else { x as u64 }
+    }
+}
+
+impl Distribution<u64> for Geometric {
+    fn quantile(&self, p: f64) -> u64 {
+        assert!(0.0 <= p && p <= 1.0);
+        if p == 0.0 || self.p == 1.0 {
+            return 0;
+        } else if p == 1.0 {
+            return u64::max_value;
+        }
+
+        // the CDF can be inverted exactly, up to rounding.
+        let guess = ((-p).ln_1p() / self.ln_q - 1.0).ceil();
+        let guess = if guess > 0.0 { guess as u64 } else { 0 };
+        special::search_discrete_quantile(p, guess, |k| self.cumulative(k))
+    }
+
+    fn cumulative(&self, sample: u64) -> f64 {
+        -((sample as f64 + 1.0) * self.ln_q).exp_m1()
+    }
+
+    fn density(&self, sample: u64) -> f64 {
+        if self.p == 1.0 {
+            return if sample == 0 { 1.0 } else { 0.0 };
+        }
+        self.p * num::exp(sample as f64 * self.ln_q)
+    }
+}
+
+/// The negative binomial distribution `NegativeBinomial(r, p)`, the
+/// distribution of the number of failures before the `r`th success in
+/// a sequence of independent trials that each succeed with probability
+/// `p`. `r` need not be an integer.
+///
+/// This is sampled as a Poisson random variable whose mean is itself
+/// `Gamma(r, p / (1 - p))` distributed.
+pub struct NegativeBinomial {
+    priv r: f64,
+    priv p: f64,
+    // the mixing distribution of the Poisson mean, or `None` if p == 1.
+    priv gamma: Option<Gamma>
+}
+
+impl NegativeBinomial {
+    /// Construct a `NegativeBinomial` counting failures before `r`
+    /// successes, each with probability `p`. Fails if `r` is not
+    /// positive and finite, or `p` is not in (0, 1].
+    pub fn new(r: f64, p: f64) -> NegativeBinomial {
+        unwrap_params("NegativeBinomial", NegativeBinomial::try_new(r, p))
+    }
+
+    /// Construct a `NegativeBinomial` counting failures before `r`
+    /// successes, each with probability `p`, returning an error if `r`
+    /// is not positive and finite, or `p` is not in (0, 1].
+    pub fn try_new(r: f64, p: f64) -> Result<NegativeBinomial, ParameterError> {
+        if !is_positive(r) {
+            Err(ParameterError::not_positive("r"))
+        } else if !is_success_probability(p) {
+            Err(ParameterError::new("p", "must be in (0, 1]"))
+        } else {
+            let gamma = if p == 1.0 { None } else { Some(Gamma::new(r, p / (1.0 - p))) };
+            Ok(NegativeBinomial { r: r, p: p, gamma: gamma })
+        }
+    }
+}
+
+impl Sample<u64> for NegativeBinomial {
+    fn sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        match self.gamma {
+            None => 0,
+            Some(ref gamma) => {
+                let lambda = gamma.sample(rng);
+                if lambda > 0.0 {
+                    Poisson::new(lambda).sample(rng)
+                } else {
+                    0
+                }
+            }
+        }
+    }
+}
+
+impl Distribution<u64> for NegativeBinomial {
+    fn quantile(&self, p: f64) -> u64 {
+        assert!(0.0 <= p && p <= 1.0);
+        if p == 0.0 || self.p == 1.0 {
+            return 0;
+        } else if p == 1.0 {
+            return u64::max_value;
+        }
+
+        // start from the normal approximation, and then step to the
+        // exact answer.
+        let q = 1.0 - self.p;
+        let mean = self.r * q / self.p;
+        let sd = num::sqrt(self.r * q) / self.p;
+        let guess = mean + sd * special::std_normal_quantile(p);
+        let guess = if guess > 0.0 { guess.floor() as u64 } else { 0 };
+        special::search_discrete_quantile(p, guess, |k| self.cumulative(k))
+    }
+
+    fn cumulative(&self, sample: u64) -> f64 {
+        special::beta_inc(self.r, sample as f64 + 1.0, self.p)
+    }
+
+    fn density(&self, sample: u64) -> f64 {
+        if self.p == 1.0 {
+            return if sample == 0 { 1.0 } else { 0.0 };
+        }
+        let k = sample as f64;
+        let (_, ln_gamma_k_r) = (k + self.r).lgamma();
+        let (_, ln_k_factorial) = (k + 1.0).lgamma();
+        let (_, ln_gamma_r) = self.r.lgamma();
+        num::exp(ln_gamma_k_r - ln_k_factorial - ln_gamma_r +
+                 self.r * num::ln(self.p) + k * (-self.p).ln_1p())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use distributions::{Sample, Distribution};
+    use std::num;
+
+    #[test]
+    fn test_geometric() {
+        let mut rng = ::rng();
+        for &p in [1e-9, 0.01, 0.5, 1.0].iter() {
+            let geometric = Geometric::new(p);
+            let mut sum = 0.0;
+            for _ in range(0, 1000) {
+                sum += geometric.sample(&mut rng) as f64;
+            }
+            let mean = sum / 1000.0;
+            let expected = (1.0 - p) / p;
+            let sd = num::sqrt(1.0 - p) / p / num::sqrt(1000.0);
+            assert!((mean - expected).abs() <= 6.0 * sd, "p = {}, mean = {}", p, mean);
+        }
+
+        let geometric = Geometric::new(0.25);
+        assert!((geometric.density(2) - 0.25 * 0.75 * 0.75).abs() < 1e-15);
+        assert!((geometric.cumulative(2) - (1.0 - 0.75 * 0.75 * 0.75)).abs() < 1e-15);
+        for &p in [0.001, 0.25, 0.4375, 0.5, 0.999].iter() {
+            let k = geometric.quantile(p);
+            assert!(geometric.cumulative(k) >= p);
+            assert!(k == 0 || geometric.cumulative(k - 1) < p);
+        }
+        assert_eq!(Geometric::new(1.0).quantile(0.5), 0);
+        assert!(Geometric::try_new(0.0).is_err());
+        assert!(Geometric::try_new(1.1).is_err());
+    }
+
+    #[test]
+    fn test_negative_binomial() {
+        let mut rng = ::rng();
+        for &(r, p) in [(1.0, 0.5), (3.5, 0.2), (100.0, 0.9), (2.0, 1.0)].iter() {
+            let nb = NegativeBinomial::new(r, p);
+            let mut sum = 0.0;
+            for _ in range(0, 1000) {
+                sum += nb.sample(&mut rng) as f64;
+            }
+            let mean = sum / 1000.0;
+            let expected = r * (1.0 - p) / p;
+            let sd = num::sqrt(r * (1.0 - p)) / p / num::sqrt(1000.0);
+            assert!((mean - expected).abs() <= 6.0 * sd, "r = {}, p = {}, mean = {}", r, p, mean);
+        }
+
+        // NegativeBinomial(1, p) is Geometric(p).
+        let nb = NegativeBinomial::new(1.0, 0.25);
+        let geometric = Geometric::new(0.25);
+        for k in range(0u64, 10) {
+            assert!((nb.density(k) - geometric.density(k)).abs() < 1e-15);
+            assert!((nb.cumulative(k) - geometric.cumulative(k)).abs() < 1e-14);
+        }
+
+        // NegativeBinomial(3, 1/2) has P(X = 2) = (4 choose 2) / 2^5.
+        let nb = NegativeBinomial::new(3.0, 0.5);
+        assert!((nb.density(2) - 6.0 / 32.0).abs() < 1e-15);
+        for &p in [0.001, 0.3, 0.5, 0.999].iter() {
+            let k = nb.quantile(p);
+            assert!(nb.cumulative(k) >= p);
+            assert!(k == 0 || nb.cumulative(k - 1) < p);
+        }
+        assert!(NegativeBinomial::try_new(0.0, 0.5).is_err());
+        assert!(NegativeBinomial::try_new(1.0, 0.0).is_err());
+    }
+}
@@ -0,0 +1,496 @@
+use Rng;
+use super::{Sample, Distribution, special};
+use super::{ParameterError, unwrap_params};
+use std::{cmp, num};
+
+/// The value of the (reduced) mode below which samples are generated
+/// by inversion, rather than by H2PE.
+static H2PE_THRESHOLD: f64 = 10.0;
+
+/// The relative size of the terms at which the sums in `cumulative`
+/// are stopped.
+static EPSILON: f64 = 1e-16;
+
+/// The hypergeometric distribution `Hypergeometric(N, K, n)`, the
+/// distribution of the number of successes when drawing `n` items
+/// without replacement from a population of `N` items, of which `K`
+/// are successes.
+///
+/// When the distribution is narrow this is sampled by sequential
+/// search of the cumulative distribution function (inversion),
+/// starting from the mode, otherwise by the H2PE algorithm of V.
+/// Kachitvichyanukul and B. W. Schmeiser, *Computer Generation of
+/// Hypergeometric Random Variates* (1985), which takes constant
+/// expected time.
+pub struct Hypergeometric {
+    priv population: u64,
+    priv successes: u64,
+    priv draws: u64,
+    // the smallest and largest possible values.
+    priv low: u64,
+    priv high: u64,
+    // the most likely value, and its probability.
+    priv mode: u64,
+    priv mode_prob: f64,
+    priv repr: HypergeometricRepr
+}
+
+enum HypergeometricRepr {
+    /// Inversion, searching outwards from the mode.
+    Inversion,
+    H2pe(HypergeometricH2pe)
+}
+
+/// The constants of the H2PE algorithm for given parameters. These
+/// are for a reduced distribution of `k` draws from `n1` successes
+/// and `n2` failures, with `n1 <= n2` and `k <= (n1 + n2) / 2`, which
+/// the original distribution is recovered from by the symmetries of
+/// swapping successes with failures and drawn items with undrawn ones.
+struct HypergeometricH2pe {
+    n1: f64,
+    n2: f64,
+    k: f64,
+    draws: u64,
+    flip_successes: bool,
+    flip_draws: bool,
+    m: f64,
+    a: f64,
+    x_l: f64,
+    x_r: f64,
+    lambda_l: f64,
+    lambda_r: f64,
+    p1: f64,
+    p2: f64,
+    p3: f64
+}
+
+/// `ln(x!)`, extended to non-integer `x` by the gamma function.
+fn ln_factorial(x: f64) -> f64 {
+    let (_, ln_gamma) = (x + 1.0).lgamma();
+    ln_gamma
+}
+
+impl Hypergeometric {
+    /// Construct a `Hypergeometric` for `draws` draws from a population
+    /// of size `population` containing `successes` successes. Fails if
+    /// either `successes` or `draws` is larger than `population`.
+    pub fn new(population: u64, successes: u64, draws: u64) -> Hypergeometric {
+        unwrap_params("Hypergeometric", Hypergeometric::try_new(population, successes, draws))
+    }
+
+    /// Construct a `Hypergeometric` for `draws` draws from a population
+    /// of size `population` containing `successes` successes, returning
+    /// an error if either `successes` or `draws` is larger than
+    /// `population`.
+    pub fn try_new(population: u64, successes: u64,
+                   draws: u64) -> Result<Hypergeometric, ParameterError> {
+        if successes > population {
+            return Err(ParameterError::new("successes", "must be at most the population size"));
+        } else if draws > population {
+            return Err(ParameterError::new("draws", "must be at most the population size"));
+        }
+
+        let failures = population - successes;
+        let low = if draws > failures { draws - failures } else { 0 };
+        let high = cmp::min(draws, successes);
+        let mode = ((draws as f64 + 1.0) * (successes as f64 + 1.0) /
+                    (population as f64 + 2.0)).floor() as u64;
+        let repr = match HypergeometricH2pe::new(population, successes, draws) {
+            Some(h2pe) => H2pe(h2pe),
+            None => Inversion
+        };
+        let mut dist = Hypergeometric {
+            population: population,
+            successes: successes,
+            draws: draws,
+            low: low,
+            high: high,
+            mode: cmp::max(low, cmp::min(mode, high)),
+            mode_prob: 0.0,
+            repr: repr
+        };
+        // this is computed in log space, so it is accurate even when
+        // the probabilities of the extremes underflow.
+        dist.mode_prob = dist.density(dist.mode);
+        Ok(dist)
+    }
+
+    /// The ratio `P(X = k + 1) / P(X = k)`, for `low <= k < high`.
+    fn prob_ratio(&self, k: u64) -> f64 {
+        let k = k as f64;
+        let n = self.population as f64;
+        let s = self.successes as f64;
+        let d = self.draws as f64;
+        (s - k) * (d - k) / ((k + 1.0) * (n - s - d + k + 1.0))
+    }
+}
+
+impl HypergeometricH2pe {
+    /// The H2PE constants for these parameters, or `None` if the mode
+    /// of the reduced distribution is below `H2PE_THRESHOLD`, in which
+    /// case inversion is used instead.
+    fn new(population: u64, successes: u64, draws: u64) -> Option<HypergeometricH2pe> {
+        let flip_successes = successes > population - successes;
+        let n1 = if flip_successes { population - successes } else { successes };
+        let flip_draws = draws > population - draws;
+        let k = if flip_draws { population - draws } else { draws };
+
+        let n = population as f64;
+        let n1 = n1 as f64;
+        let n2 = n - n1;
+        let k = k as f64;
+        let m = ((k + 1.0) * (n1 + 1.0) / (n + 2.0)).floor();
+        if m < H2PE_THRESHOLD {
+            return None;
+        }
+
+        // `a - ln_f(x)` is the logarithm of the ratio of the density at
+        // `x` to that at the mode.
+        let ln_f = |x: f64| {
+            ln_factorial(x) + ln_factorial(n1 - x) + ln_factorial(k - x) + ln_factorial(n2 - k + x)
+        };
+        let a = ln_f(m);
+
+        // the majorising function is flat on [x_l, x_r), with
+        // exponential tails on either side.
+        let d = 1.5 * num::sqrt((n - k) * k * n1 * n2 / ((n - 1.0) * n * n)) + 0.5;
+        let x_l = m - d + 0.5;
+        let x_r = m + d + 0.5;
+        let k_l = num::exp(a - ln_f(x_l));
+        let k_r = num::exp(a - ln_f(x_r - 1.0));
+        let lambda_l = -num::ln(x_l * (n2 - k + x_l) / ((n1 - x_l + 1.0) * (k - x_l + 1.0)));
+        let lambda_r = -num::ln((n1 - x_r + 1.0) * (k - x_r + 1.0) / (x_r * (n2 - k + x_r)));
+        let p1 = 2.0 * d;
+        let p2 = p1 + k_l / lambda_l;
+        let p3 = p2 + k_r / lambda_r;
+
+        Some(HypergeometricH2pe {
+            n1: n1,
+            n2: n2,
+            k: k,
+            draws: draws,
+            flip_successes: flip_successes,
+            flip_draws: flip_draws,
+            m: m,
+            a: a,
+            x_l: x_l,
+            x_r: x_r,
+            lambda_l: lambda_l,
+            lambda_r: lambda_r,
+            p1: p1,
+            p2: p2,
+            p3: p3
+        })
+    }
+
+    /// Sample the reduced distribution.
+    fn sample_reduced<R: Rng>(&self, rng: &mut R) -> f64 {
+        let (n1, n2, k, m) = (self.n1, self.n2, self.k, self.m);
+        loop {
+            let u = rng.gen::<f64>() * self.p3;
+            let mut v = rng.gen::<f64>();
+
+            let y = if u <= self.p1 {
+                (self.x_l + u).floor()
+            } else if u <= self.p2 {
+                let y = (self.x_l + num::ln(v) / self.lambda_l).floor();
+                if y < 0.0 {
+                    continue;
+                }
+                v *= (u - self.p1) * self.lambda_l;
+                y
+            } else {
+                let y = (self.x_r - num::ln(v) / self.lambda_r).floor();
+                if y > num::min(n1, k) {
+                    continue;
+                }
+                v *= (u - self.p2) * self.lambda_r;
+                y
+            };
+
+            if m < 100.0 || y <= 50.0 {
+                // evaluate f(y) explicitly, by the recurrence for the
+                // ratio of consecutive probabilities.
+                let mut f = 1.0;
+                let mut i = num::min(m, y) + 1.0;
+                while i <= num::max(m, y) {
+                    f *= (n1 - i + 1.0) * (k - i + 1.0) / (i * (n2 - k + i));
+                    i += 1.0;
+                }
+                if (m < y && v <= f) || (m >= y && v * f <= 1.0) {
+                    return y;
+                }
+                continue;
+            }
+
+            // squeeze using upper and lower bounds on ln f(y).
+            fn pow4(x: f64) -> f64 { (x * x) * (x * x) }
+            fn series(x: f64) -> f64 { x * (1.0 + x * (-0.5 + x / 3.0)) }
+            fn remainder(x: f64, weight: f64) -> f64 {
+                if x < 0.0 { weight * pow4(x) / (1.0 + x) } else { weight * pow4(x) }
+            }
+            let y1 = y + 1.0;
+            let ym = y - m;
+            let yn = n1 - y + 1.0;
+            let yk = k - y + 1.0;
+            let nk = n2 - k + y1;
+            let r = -ym / y1;
+            let s = ym / yn;
+            let t = ym / yk;
+            let e = -ym / nk;
+            let g = yn * yk / (y1 * nk) - 1.0;
+            let dg = if g < 0.0 { 1.0 + g } else { 1.0 };
+            let gu = series(g);
+            let gl = gu - pow4(g) / (4.0 * dg);
+            let xm = m + 0.5;
+            let xn = n1 - m + 0.5;
+            let xk = k - m + 0.5;
+            let nm = n2 - k + xm;
+            let ub = xm * series(r) + xn * series(s) + xk * series(t) + nm * series(e) +
+                y * gu - m * gl + 0.0034;
+            let ln_v = num::ln(v);
+            if ln_v > ub {
+                continue;
+            }
+            let d = remainder(r, xm) + remainder(s, xn) + remainder(t, xk) + remainder(e, nm);
+            if ln_v < ub - 0.25 * d + (y + m) * (gl - gu) - 0.0078 {
+                return y;
+            }
+
+            // the final acceptance test.
+            let ln_f = ln_factorial(y) + ln_factorial(n1 - y) + ln_factorial(k - y) +
+                ln_factorial(n2 - k + y);
+            if ln_v <= self.a - ln_f {
+                return y;
+            }
+        }
+    }
+}
+
+impl Sample<u64> for HypergeometricH2pe {
+    fn sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        let mut x = self.sample_reduced(rng) as u64;
+        if self.flip_draws {
+            // the successes among the undrawn items were sampled.
+            x = self.n1 as u64 - x;
+        }
+        if self.flip_successes {
+            // the failures were sampled.
+            x = self.draws - x;
+        }
+        x
+    }
+}
+
+impl Sample<u64> for Hypergeometric {
+    fn sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        match self.repr {
+            Inversion => {
+                // search alternately below and above the mode, so that
+                // this takes time proportional to the standard
+                // deviation.
+                loop {
+                    let mut u = rng.gen::<f64>() - self.mode_prob;
+                    if u <= 0.0 {
+                        return self.mode;
+                    }
+                    let (mut below, mut below_prob) = (self.mode, self.mode_prob);
+                    let (mut above, mut above_prob) = (self.mode, self.mode_prob);
+                    while below > self.low || above < self.high {
+                        if below > self.low {
+                            below -= 1;
+                            below_prob /= self.prob_ratio(below);
+                            u -= below_prob;
+                            if u <= 0.0 {
+                                return below;
+                            }
+                        }
+                        if above < self.high {
+                            above_prob *= self.prob_ratio(above);
+                            above += 1;
+                            u -= above_prob;
+                            if u <= 0.0 {
+                                return above;
+                            }
+                        }
+                    }
+                    // `u` can only be left over through rounding
+                    // errors, so try again.
+                }
+            }
+            H2pe(ref h2pe) => h2pe.sample(rng)
+        }
+    }
+}
+
+impl Distribution<u64> for Hypergeometric {
+    fn quantile(&self, p: f64) -> u64 {
+        assert!(0.0 <= p && p <= 1.0);
+        if p == 0.0 {
+            return self.low;
+        } else if p == 1.0 {
+            return self.high;
+        }
+
+        // start from the normal approximation, and then step to the
+        // exact answer.
+        let n = self.population as f64;
+        let s = self.successes as f64;
+        let d = self.draws as f64;
+        let mean = d * s / n;
+        let sd = if n > 1.0 { num::sqrt(mean * (n - s) / n * (n - d) / (n - 1.0)) } else { 0.0 };
+        let guess = mean + sd * special::std_normal_quantile(p);
+        let guess = if guess <= self.low as f64 {
+            self.low
+        } else if guess >= self.high as f64 {
+            self.high
+        } else {
+            guess.floor() as u64
+        };
+        special::search_discrete_quantile(p, guess, |k| self.cumulative(k))
+    }
+
+    fn cumulative(&self, sample: u64) -> f64 {
+        if sample < self.low {
+            return 0.0;
+        } else if sample >= self.high {
+            return 1.0;
+        }
+
+        // sum the tail that doesn't contain the mode, outwards from
+        // `sample`; the probabilities shrink away from the mode, so
+        // this can stop once they are negligible.
+        if sample < self.mode {
+            let mut k = sample;
+            let mut prob = self.density(k);
+            let mut sum = prob;
+            while k > self.low && prob > sum * EPSILON {
+                k -= 1;
+                prob /= self.prob_ratio(k);
+                sum += prob;
+            }
+            sum
+        } else {
+            let mut k = sample + 1;
+            let mut prob = self.density(k);
+            let mut sum = prob;
+            while k < self.high && prob > sum * EPSILON {
+                prob *= self.prob_ratio(k);
+                k += 1;
+                sum += prob;
+            }
+            1.0 - sum
+        }
+    }
+
+    fn density(&self, sample: u64) -> f64 {
+        if sample < self.low || sample > self.high {
+            return 0.0;
+        }
+        let n = self.population as f64;
+        let s = self.successes as f64;
+        let d = self.draws as f64;
+        let k = sample as f64;
+        num::exp(special::ln_choose(s, k) + special::ln_choose(n - s, d - k) -
+                 special::ln_choose(n, d))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use distributions::{Sample, Distribution};
+    use std::num;
+
+    #[test]
+    fn test_hypergeometric_sample() {
+        let mut rng = ::rng();
+        for &(n, s, d) in [(10u64, 3u64, 5u64), (100, 0, 50), (100, 100, 50),
+                           (100, 60, 70), (1000000, 500, 10000), (2000, 1000, 1000),
+                           (5000, 4600, 3000), (1000000, 500000, 500000)].iter() {
+            let hyper = Hypergeometric::new(n, s, d);
+            let mut sum = 0.0;
+            for _ in range(0, 1000) {
+                let x = hyper.sample(&mut rng);
+                assert!(x <= s && x <= d && d - x <= n - s);
+                sum += x as f64;
+            }
+            let (n, s, d) = (n as f64, s as f64, d as f64);
+            let mean = sum / 1000.0;
+            let expected = d * s / n;
+            let sd = num::sqrt(d * s / n * (n - s) / n * (n - d) / (n - 1.0) / 1000.0);
+            assert!((mean - expected).abs() <= 6.0 * sd, "mean = {}, expected {}", mean, expected);
+        }
+    }
+
+    #[test]
+    fn test_hypergeometric_distribution() {
+        // drawing 2 from {S, S, F, F}: P(X = k) = [1, 4, 1] / 6.
+        let hyper = Hypergeometric::new(4, 2, 2);
+        let probs = [1.0 / 6.0, 4.0 / 6.0, 1.0 / 6.0];
+        let mut cumulative = 0.0;
+        for (k, &prob) in probs.iter().enumerate() {
+            cumulative += prob;
+            assert!((hyper.density(k as u64) - prob).abs() < 1e-15);
+            assert!((hyper.cumulative(k as u64) - cumulative).abs() < 1e-15);
+        }
+        assert_eq!(hyper.quantile(0.0), 0);
+        assert_eq!(hyper.quantile(0.5), 1);
+        assert_eq!(hyper.quantile(1.0), 2);
+
+        // drawing 70 from 60 successes and 40 failures gives at least 30
+        // successes.
+        let hyper = Hypergeometric::new(100, 60, 70);
+        assert_eq!(hyper.density(29), 0.0);
+        assert_eq!(hyper.cumulative(29), 0.0);
+        assert!(hyper.density(30) > 0.0);
+        assert_eq!(hyper.cumulative(60), 1.0);
+    }
+
+    #[test]
+    fn test_hypergeometric_large() {
+        // P(X = 0) is about 1e-600 here, so all of the computations
+        // have to avoid starting from the ends of the range.
+        let hyper = Hypergeometric::new(2000, 1000, 1000);
+        assert_eq!(hyper.cumulative(0), 0.0);
+        for &(k, expected) in [(450u64, 4.692947220165792e-06), (480, 0.04055503028956669),
+                               (500, 0.517834551951791), (520, 0.9666548918351516)].iter() {
+            let actual = hyper.cumulative(k);
+            assert!((actual - expected).abs() <= 1e-11 * expected,
+                    "cumulative({}) = {}, expected {}", k, actual, expected);
+        }
+        for &p in [0.001, 0.2, 0.5, 0.999].iter() {
+            let k = hyper.quantile(p);
+            assert!(hyper.cumulative(k) >= p);
+            assert!(hyper.cumulative(k - 1) < p);
+        }
+
+        let mut rng = ::rng();
+        let mut sum = 0.0;
+        let mut below_median = 0;
+        for _ in range(0, 10000) {
+            let x = hyper.sample(&mut rng);
+            sum += x as f64;
+            if x < 500 { below_median += 1; }
+        }
+        // the standard deviation of X is about 11.2.
+        assert!((sum / 10000.0 - 500.0).abs() <= 6.0 * 11.2 / 100.0, "mean = {}", sum / 10000.0);
+        // P(X < 500) = (1 - P(X = 500)) / 2 by symmetry, about 0.482.
+        let expected = (1.0 - hyper.density(500)) / 2.0;
+        assert!((expected - hyper.cumulative(499)).abs() < 1e-11);
+        let sd = num::sqrt(expected * (1.0 - expected) / 10000.0);
+        assert!((below_median as f64 / 10000.0 - expected).abs() <= 6.0 * sd);
+
+        // the same symmetry for a population too large to sum directly.
+        let hyper = Hypergeometric::new(1000000, 500000, 500000);
+        let expected = (1.0 - hyper.density(250000)) / 2.0;
+        assert!((hyper.cumulative(249999) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_hypergeometric_try_new() {
+        assert!(Hypergeometric::try_new(10, 11, 5).is_err());
+        assert!(Hypergeometric::try_new(10, 5, 11).is_err());
+        assert!(Hypergeometric::try_new(10, 10, 10).is_ok());
+    }
+}
@@ -13,12 +13,30 @@
 
 use std::vec;
 use Rng;
-use Rand;
+
+pub use self::gamma::{Gamma, ChiSquared, FisherF, StudentT};
+pub use self::beta::{Beta, Dirichlet};
+pub use self::poisson::Poisson;
+pub use self::binomial::Binomial;
+pub use self::geometric::{Geometric, NegativeBinomial};
+pub use self::hypergeometric::Hypergeometric;
+pub use self::weighted::WeightedIndex;
+pub use self::uniform::{Uniform, SampleRange};
+pub use self::charset::{CharsetDist, StringDist};
 
 pub mod ziggurat_tables;
 pub mod normal;
 pub mod gamma;
+pub mod beta;
 pub mod exp;
+pub mod poisson;
+pub mod binomial;
+pub mod geometric;
+pub mod hypergeometric;
+pub mod weighted;
+pub mod uniform;
+pub mod charset;
+mod special;
 
 pub trait Sample<Support> {
     fn sample<R: Rng>(&self, &mut R) -> Support;
@@ -31,6 +49,60 @@ pub trait Distribution<Support>: Sample<Support> {
     fn density(&self, sample: Support) -> f64;
 }
 
+/// The error returned by the `try_new` constructors of the
+/// distributions in this module when given invalid parameters.
+#[deriving(Eq, Clone)]
+pub struct ParameterError {
+    priv param: &'static str,
+    priv reason: &'static str
+}
+
+impl ParameterError {
+    /// Create an error for a parameter `param` that failed to satisfy
+    /// `reason`.
+    pub fn new(param: &'static str, reason: &'static str) -> ParameterError {
+        ParameterError { param: param, reason: reason }
+    }
+
+    /// Create an error for a parameter that is infinite or NaN.
+    pub fn not_finite(param: &'static str) -> ParameterError {
+        ParameterError::new(param, "must be finite")
+    }
+
+    /// Create an error for a parameter that is not a positive, finite
+    /// number.
+    pub fn not_positive(param: &'static str) -> ParameterError {
+        ParameterError::new(param, "must be positive and finite")
+    }
+
+    /// The name of the invalid parameter, e.g. `"sd"`.
+    pub fn param(&self) -> &'static str { self.param }
+
+    /// The requirement that the parameter failed to satisfy, e.g.
+    /// `"must be finite"`.
+    pub fn reason(&self) -> &'static str { self.reason }
+}
+
+impl ToStr for ParameterError {
+    fn to_str(&self) -> ~str {
+        format!("invalid parameter `{}`: {}", self.param, self.reason)
+    }
+}
+
+/// Is `x` a positive, finite number? (This is false for NaN.)
+fn is_positive(x: f64) -> bool {
+    x > 0.0 && x.is_finite()
+}
+
+/// Unwrap the result of the `try_new` constructor of the distribution
+/// `name`, failing with a description of the invalid parameter.
+fn unwrap_params<D>(name: &'static str, result: Result<D, ParameterError>) -> D {
+    match result {
+        Ok(dist) => dist,
+        Err(e) => fail!("{}::new: {}", name, e.to_str())
+    }
+}
+
 pub struct ListDist<I,E> {
     int: I,
     elem: E
@@ -42,15 +114,6 @@ impl<I: Sample<uint>, Sup, E : Sample<Sup>>
         vec::from_fn(len, |_| self.elem.sample(rng))
     }
 }
-pub struct RangeDist<I> {
-    low: I,
-    range: I
-}
-impl<I: Rem<I,I> + Add<I,I> + Rand> Sample<I> for RangeDist<I> {
-    fn sample<R: Rng>(&self, rng: &mut R) -> I {
-        self.low + (rng.gen::<I>() % self.range)
-    }
-}
 pub struct JointDistribution<T> { t: T }
 impl<S1, S2, D1: Sample<S1>, D2: Sample<S2>>
     Sample<(S1, S2)> for JointDistribution<(D1,D2)> {
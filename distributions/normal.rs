@@ -1,22 +1,40 @@
 use Rand;
 use Rng;
-use super::{ziggurat_tables, Sample, Distribution};
+use super::{ziggurat_tables, special, Sample, Distribution};
+use super::{ParameterError, is_positive, unwrap_params};
 use super::ziggurat_tables::ziggurat;
 
 use std::{num};
 
+/// The normal distribution `N(mean, sd^2)`.
 pub struct Normal {
-    mean: f64,
-    sd: f64
+    priv mean: f64,
+    priv sd: f64
 }
 
 impl Normal {
-    fn new(mean: f64, sd: f64) -> Normal {
-        assert!(sd > 0.0);
-        Normal { mean: mean, sd: sd }
+    /// Construct a `Normal` with the given mean and standard
+    /// deviation. Fails if `mean` is not finite, or `sd` is not
+    /// positive and finite.
+    pub fn new(mean: f64, sd: f64) -> Normal {
+        unwrap_params("Normal", Normal::try_new(mean, sd))
     }
 
-    fn standard() -> Normal {
+    /// Construct a `Normal` with the given mean and standard
+    /// deviation, returning an error if `mean` is not finite, or `sd`
+    /// is not positive and finite.
+    pub fn try_new(mean: f64, sd: f64) -> Result<Normal, ParameterError> {
+        if !mean.is_finite() {
+            Err(ParameterError::not_finite("mean"))
+        } else if !is_positive(sd) {
+            Err(ParameterError::not_positive("sd"))
+        } else {
+            Ok(Normal { mean: mean, sd: sd })
+        }
+    }
+
+    /// Construct the standard normal distribution, `N(0, 1)`.
+    pub fn standard() -> Normal {
         Normal { mean: 0.0, sd: 1.0 }
     }
 }
@@ -30,16 +48,24 @@ impl Sample<f64> for Normal {
 impl Distribution<f64> for Normal {
     fn quantile(&self, p: f64) -> f64 {
         assert!(0.0 <= p && p <= 1.0);
-        fail!("unimplemented");
+        if p == 0.0 {
+            Float::neg_infinity()
+        } else if p == 1.0 {
+            Float::infinity()
+        } else {
+            self.mean + self.sd * special::std_normal_quantile(p)
+        }
     }
 
-    fn cumulative(&self, _sample: f64) -> f64 {
-        //0.5 + 0.5 * f64::erf((sample - self.mean) / num::sqrt(self.sd * Real::two_pi()))
-        fail!() // what
+    fn cumulative(&self, sample: f64) -> f64 {
+        // Phi(z) = erfc(-z/sqrt(2)) / 2, which (unlike using `erf`)
+        // stays accurate far into the lower tail.
+        let standard = (sample - self.mean) / self.sd;
+        0.5 * special::erfc(-standard * Real::frac_1_sqrt2())
     }
     fn density(&self, sample: f64) -> f64 {
         let standard = (sample - self.mean) / self.sd;
-        num::exp(-0.5 * standard * standard) / num::sqrt(self.sd * Real::two_pi())
+        num::exp(-0.5 * standard * standard) / (self.sd * num::sqrt(Real::two_pi()))
     }
 }
 
@@ -98,6 +124,84 @@ impl Rand for StandardNormal {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use distributions::{Distribution, ParameterError};
+
+    // reference values of the standard normal CDF, computed to double
+    // precision.
+    static CDF_TABLE: [(f64, f64), .. 9] = [(-8.0, 6.220960574271819e-16),
+                                            (-3.0, 0.0013498980316300957),
+                                            (-1.0, 0.15865525393145707),
+                                            (-0.5, 0.3085375387259869),
+                                            (0.0, 0.5),
+                                            (0.5, 0.6914624612740131),
+                                            (1.0, 0.8413447460685429),
+                                            (1.96, 0.9750021048517795),
+                                            (3.0, 0.9986501019683699)];
+
+    #[test]
+    fn test_normal_cumulative() {
+        let normal = Normal::standard();
+        for &(x, p) in CDF_TABLE.iter() {
+            let actual = normal.cumulative(x);
+            assert!((actual - p).abs() <= 1e-14 * p, "cdf({}): expected {}, got {}", x, p, actual);
+        }
+
+        let normal = Normal::new(10.0, 2.0);
+        assert!((normal.cumulative(12.0) - 0.8413447460685429).abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_normal_quantile() {
+        let normal = Normal::standard();
+        for &(x, p) in CDF_TABLE.iter() {
+            let actual = normal.quantile(p);
+            assert!((actual - x).abs() <= 1e-13 * (1.0 + x.abs()),
+                    "quantile({}): expected {}, got {}", p, x, actual);
+        }
+        assert_eq!(normal.quantile(0.0), Float::neg_infinity());
+        assert_eq!(normal.quantile(1.0), Float::infinity());
+
+        let normal = Normal::new(10.0, 2.0);
+        assert!((normal.quantile(0.975) - (10.0 + 2.0 * 1.959963984540054)).abs() < 1e-13);
+    }
+
+    #[test]
+    fn test_normal_try_new() {
+        assert!(Normal::try_new(-1.0, 0.5).is_ok());
+        assert!(Normal::try_new(0.0, 0.0).is_err());
+        assert!(Normal::try_new(0.0, -1.0).is_err());
+        assert!(Normal::try_new(0.0, Float::infinity()).is_err());
+        assert!(Normal::try_new(Float::NaN(), 1.0).is_err());
+        match Normal::try_new(0.0, Float::NaN()) {
+            Err(e) => {
+                assert_eq!(e, ParameterError::not_positive("sd"));
+                assert_eq!(e.param(), "sd");
+            }
+            Ok(_) => fail!("NaN sd accepted")
+        }
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_normal_invalid_sd() {
+        Normal::new(0.0, -1.0);
+    }
+
+    #[test]
+    fn test_normal_density() {
+        let normal = Normal::standard();
+        assert!((normal.density(0.0) - 0.3989422804014327).abs() < 1e-16);
+        assert!((normal.density(1.0) - 0.24197072451914337).abs() < 1e-16);
+
+        // the density scales as 1/sd.
+        let normal = Normal::new(3.0, 4.0);
+        assert!((normal.density(7.0) - 0.24197072451914337 / 4.0).abs() < 1e-16);
+    }
+}
+
 #[cfg(test)]
 mod bench {
     use extra::test::BenchHarness;
@@ -0,0 +1,200 @@
+use Rng;
+use super::{Sample, Distribution, special};
+use super::{ParameterError, is_positive, unwrap_params};
+use std::{num, u64};
+
+/// The mean below which samples are generated by inversion, rather
+/// than by transformed rejection.
+static INVERSION_THRESHOLD: f64 = 10.0;
+
+/// The Poisson distribution `Poisson(lambda)`, the distribution of the
+/// number of events occurring in a fixed interval, when they occur
+/// independently at an average rate of `lambda` per interval.
+///
+/// For `lambda < 10` this is sampled by sequential search of the
+/// cumulative distribution function (inversion), taking time
+/// proportional to `lambda`. For larger `lambda` it uses the
+/// transformed rejection method PTRS of W. Hörmann, [*The Transformed
+/// Rejection Method for Generating Poisson Random
+/// Variables*](http://epub.wu.ac.at/1242/) (1993), which takes
+/// constant expected time.
+pub struct Poisson {
+    priv lambda: f64,
+    priv repr: PoissonRepr
+}
+
+enum PoissonRepr {
+    /// Inversion, storing `e^-lambda`.
+    Inversion(f64),
+    Ptrs(PoissonPtrs)
+}
+
+/// The constants of the PTRS algorithm for a given `lambda`.
+struct PoissonPtrs {
+    lambda: f64,
+    ln_lambda: f64,
+    a: f64,
+    b: f64,
+    ln_inv_alpha: f64,
+    v_r: f64
+}
+
+impl Poisson {
+    /// Construct a `Poisson` with mean `lambda`. Fails if `lambda` is
+    /// not positive and finite.
+    pub fn new(lambda: f64) -> Poisson {
+        unwrap_params("Poisson", Poisson::try_new(lambda))
+    }
+
+    /// Construct a `Poisson` with mean `lambda`, returning an error if
+    /// `lambda` is not positive and finite.
+    pub fn try_new(lambda: f64) -> Result<Poisson, ParameterError> {
+        if !is_positive(lambda) {
+            return Err(ParameterError::not_positive("lambda"));
+        }
+
+        let repr = if lambda < INVERSION_THRESHOLD {
+            Inversion(num::exp(-lambda))
+        } else {
+            Ptrs(PoissonPtrs::new(lambda))
+        };
+        Ok(Poisson { lambda: lambda, repr: repr })
+    }
+}
+
+impl PoissonPtrs {
+    fn new(lambda: f64) -> PoissonPtrs {
+        let b = 0.931 + 2.53 * num::sqrt(lambda);
+        PoissonPtrs {
+            lambda: lambda,
+            ln_lambda: num::ln(lambda),
+            a: -0.059 + 0.02483 * b,
+            b: b,
+            ln_inv_alpha: num::ln(1.1239 + 1.1328 / (b - 3.4)),
+            v_r: 0.9277 - 3.6224 / (b - 2.0)
+        }
+    }
+}
+
+impl Sample<u64> for PoissonPtrs {
+    fn sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        loop {
+            let u = rng.gen::<f64>() - 0.5;
+            let v = rng.gen::<f64>();
+            let us = 0.5 - u.abs();
+            let k = ((2.0 * self.a / us + self.b) * u + self.lambda + 0.43).floor();
+
+            // the fast acceptance region, which covers most samples.
+            if us >= 0.07 && v <= self.v_r {
+                return k as u64;
+            }
+            if k < 0.0 || (us < 0.013 && v > us) {
+                continue;
+            }
+
+            let (_, ln_k_factorial) = (k + 1.0).lgamma();
+            if num::ln(v) + self.ln_inv_alpha - num::ln(self.a / (us * us) + self.b) <=
+                -self.lambda + k * self.ln_lambda - ln_k_factorial {
+                return k as u64;
+            }
+        }
+    }
+}
+
+impl Sample<u64> for Poisson {
+    fn sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        match self.repr {
+            Inversion(exp_neg_lambda) => {
+                let u = rng.gen::<f64>();
+                let mut k = 0;
+                let mut prob = exp_neg_lambda;
+                let mut cumulative = prob;
+                // the `prob > 0` check guards against `cumulative`
+                // rounding to something less than `u` in the far tail.
+                while u > cumulative && prob > 0.0 {
+                    k += 1;
+                    prob *= self.lambda / k as f64;
+                    cumulative += prob;
+                }
+                k
+            }
+            Ptrs(ref ptrs) => ptrs.sample(rng)
+        }
+    }
+}
+
+impl Distribution<u64> for Poisson {
+    fn quantile(&self, p: f64) -> u64 {
+        assert!(0.0 <= p && p <= 1.0);
+        if p == 0.0 {
+            return 0;
+        } else if p == 1.0 {
+            return u64::max_value;
+        }
+
+        // start from the normal approximation, and then step to the
+        // exact answer, which is normally only a few steps away.
+        let guess = self.lambda + num::sqrt(self.lambda) * special::std_normal_quantile(p);
+        let guess = if guess > 0.0 { guess.floor() as u64 } else { 0 };
+        special::search_discrete_quantile(p, guess, |k| self.cumulative(k))
+    }
+
+    fn cumulative(&self, sample: u64) -> f64 {
+        special::gamma_q(sample as f64 + 1.0, self.lambda)
+    }
+
+    fn density(&self, sample: u64) -> f64 {
+        let k = sample as f64;
+        let (_, ln_k_factorial) = (k + 1.0).lgamma();
+        num::exp(k * num::ln(self.lambda) - self.lambda - ln_k_factorial)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use distributions::{Sample, Distribution};
+    use std::num;
+
+    #[test]
+    fn test_poisson_sample() {
+        let mut rng = ::rng();
+        for &lambda in [0.1, 2.5, 9.99, 10.0, 50.0, 1e6].iter() {
+            let poisson = Poisson::new(lambda);
+            let mut sum = 0;
+            for _ in range(0, 1000) {
+                sum += poisson.sample(&mut rng);
+            }
+            // the standard error of the mean is sqrt(lambda / 1000)
+            let mean = sum as f64 / 1000.0;
+            assert!((mean - lambda).abs() < 6.0 * num::sqrt(lambda / 1000.0),
+                    "lambda = {}, mean = {}", lambda, mean);
+        }
+    }
+
+    #[test]
+    fn test_poisson_distribution() {
+        let poisson = Poisson::new(2.0);
+        let e2 = num::exp(-2.0);
+        assert!((poisson.density(0) - e2).abs() < 1e-16);
+        assert!((poisson.density(3) - 8.0 / 6.0 * e2).abs() < 1e-15);
+        assert!((poisson.cumulative(0) - e2).abs() < 1e-15);
+        assert!((poisson.cumulative(2) - 5.0 * e2).abs() < 1e-15);
+
+        for &lambda in [0.5, 7.0, 40.0, 1000.0].iter() {
+            let poisson = Poisson::new(lambda);
+            for &p in [0.001, 0.2, 0.5, 0.7, 0.999].iter() {
+                let k = poisson.quantile(p);
+                assert!(poisson.cumulative(k) >= p);
+                assert!(k == 0 || poisson.cumulative(k - 1) < p);
+            }
+        }
+    }
+
+    #[test]
+    fn test_poisson_try_new() {
+        assert!(Poisson::try_new(0.0).is_err());
+        assert!(Poisson::try_new(-1.0).is_err());
+        assert!(Poisson::try_new(Float::NaN()).is_err());
+    }
+}
@@ -0,0 +1,360 @@
+//! Special functions used to compute the density, cumulative
+//! distribution and quantile functions of the distributions in this
+//! module.
+
+use std::num;
+
+/// The relative accuracy that the series and continued fractions
+/// below are iterated to.
+static EPSILON: f64 = 1e-15;
+/// A number near the smallest representable `f64`, used to stop the
+/// modified Lentz's method from dividing by zero.
+static TINY: f64 = 1e-300;
+/// The maximum number of terms of a series or continued fraction to
+/// evaluate.
+static MAX_ITERATIONS: uint = 1000;
+
+/// The logarithm of the common prefactor `x^a e^-x / Γ(a)` of the
+/// regularised incomplete gamma functions.
+fn ln_gamma_prefactor(a: f64, x: f64) -> f64 {
+    let (_, ln_gamma_a) = a.lgamma();
+    a * num::ln(x) - x - ln_gamma_a
+}
+
+/// The series expansion of `P(a, x)`, which converges quickly for `x <
+/// a + 1`.
+fn lower_gamma_series(a: f64, x: f64) -> f64 {
+    let mut denom = a;
+    let mut term = 1.0 / a;
+    let mut sum = term;
+    for _ in range(0, MAX_ITERATIONS) {
+        denom += 1.0;
+        term *= x / denom;
+        sum += term;
+        if term.abs() < sum.abs() * EPSILON {
+            break;
+        }
+    }
+    sum * num::exp(ln_gamma_prefactor(a, x))
+}
+
+/// The continued fraction expansion of `Q(a, x)`, evaluated with the
+/// modified Lentz's method, which converges quickly for `x >= a + 1`.
+fn upper_gamma_fraction(a: f64, x: f64) -> f64 {
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / TINY;
+    let mut d = 1.0 / b;
+    let mut h = d;
+    for i in range(1, MAX_ITERATIONS) {
+        let i = i as f64;
+        let an = -i * (i - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < TINY { d = TINY; }
+        c = b + an / c;
+        if c.abs() < TINY { c = TINY; }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    num::exp(ln_gamma_prefactor(a, x)) * h
+}
+
+/// The regularised lower incomplete gamma function, `P(a, x) = γ(a,
+/// x) / Γ(a)`, for `a > 0`.
+pub fn gamma_p(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        0.0
+    } else if x < a + 1.0 {
+        lower_gamma_series(a, x)
+    } else {
+        1.0 - upper_gamma_fraction(a, x)
+    }
+}
+
+/// The regularised upper incomplete gamma function, `Q(a, x) = 1 -
+/// P(a, x)`, for `a > 0`. This is computed directly (rather than as
+/// `1 - gamma_p(a, x)`) so that it is accurate in the upper tail.
+pub fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        1.0
+    } else if x < a + 1.0 {
+        1.0 - lower_gamma_series(a, x)
+    } else {
+        upper_gamma_fraction(a, x)
+    }
+}
+
+/// The error function, `erf(x) = 2/sqrt(pi) ∫_0^x e^(-t^2) dt`,
+/// computed as `P(1/2, x^2)`.
+pub fn erf(x: f64) -> f64 {
+    if x < 0.0 {
+        -gamma_p(0.5, x * x)
+    } else {
+        gamma_p(0.5, x * x)
+    }
+}
+
+/// The complementary error function, `erfc(x) = 1 - erf(x)`, computed
+/// as `Q(1/2, x^2)` so that it is accurate for large `x`.
+pub fn erfc(x: f64) -> f64 {
+    if x < 0.0 {
+        1.0 + gamma_p(0.5, x * x)
+    } else {
+        gamma_q(0.5, x * x)
+    }
+}
+
+/// Evaluate the polynomial with the given coefficients (lowest degree
+/// first) at `x`, with Horner's method.
+fn polynomial(x: f64, coeffs: &[f64]) -> f64 {
+    let mut i = coeffs.len();
+    let mut sum = 0.0;
+    while i > 0 {
+        i -= 1;
+        sum = sum * x + coeffs[i];
+    }
+    sum
+}
+
+// The coefficients of the rational approximations in Algorithm AS241.
+static AS241_A: [f64, .. 8] = [3.3871328727963666080e0, 1.3314166789178437745e+2,
+                               1.9715909503065514427e+3, 1.3731693765509461125e+4,
+                               4.5921953931549871457e+4, 6.7265770927008700853e+4,
+                               3.3430575583588128105e+4, 2.5090809287301226727e+3];
+static AS241_B: [f64, .. 8] = [1.0, 4.2313330701600911252e+1,
+                               6.8718700749205790830e+2, 5.3941960214247511077e+3,
+                               2.1213794301586595867e+4, 3.9307895800092710610e+4,
+                               2.8729085735721942674e+4, 5.2264952788528545610e+3];
+static AS241_C: [f64, .. 8] = [1.42343711074968357734e0, 4.63033784615654529590e0,
+                               5.76949722146069140550e0, 3.64784832476320460504e0,
+                               1.27045825245236838258e0, 2.41780725177450611770e-1,
+                               2.27238449892691845833e-2, 7.74545014278341407640e-4];
+static AS241_D: [f64, .. 8] = [1.0, 2.05319162663775882187e0,
+                               1.67638483018380384940e0, 6.89767334985100004550e-1,
+                               1.48103976427480074590e-1, 1.51986665636164571966e-2,
+                               5.47593808499534494600e-4, 1.05075007164441684324e-9];
+static AS241_E: [f64, .. 8] = [6.65790464350110377720e0, 5.46378491116411436990e0,
+                               1.78482653991729133580e0, 2.96560571828504891230e-1,
+                               2.65321895265761230930e-2, 1.24266094738807843860e-3,
+                               2.71155556874348757815e-5, 2.01033439929228813265e-7];
+static AS241_F: [f64, .. 8] = [1.0, 5.99832206555887937690e-1,
+                               1.36929880922735805310e-1, 1.48753612908506148525e-2,
+                               7.86869131145613259100e-4, 1.84631831751005468180e-5,
+                               1.42151175831644588870e-7, 2.04426310338993978564e-15];
+
+/// The quantile function of the standard normal distribution, for `0 <
+/// p < 1`, using M. J. Wichura's [Algorithm AS241: The Percentage
+/// Points of the Normal Distribution](http://www.jstor.org/stable/2347330)
+/// (1988), which is accurate to about 1 part in 10^16.
+pub fn std_normal_quantile(p: f64) -> f64 {
+    let q = p - 0.5;
+    if q.abs() <= 0.425 {
+        let r = 0.180625 - q * q;
+        return q * polynomial(r, AS241_A) / polynomial(r, AS241_B);
+    }
+
+    let r = num::sqrt(-num::ln(if q < 0.0 { p } else { 1.0 - p }));
+    let x = if r <= 5.0 {
+        let r = r - 1.6;
+        polynomial(r, AS241_C) / polynomial(r, AS241_D)
+    } else {
+        let r = r - 5.0;
+        polynomial(r, AS241_E) / polynomial(r, AS241_F)
+    };
+    if q < 0.0 { -x } else { x }
+}
+
+/// The logarithm of the binomial coefficient `n choose k`, for `0 <= k
+/// <= n`.
+pub fn ln_choose(n: f64, k: f64) -> f64 {
+    let (_, ln_n_factorial) = (n + 1.0).lgamma();
+    let (_, ln_k_factorial) = (k + 1.0).lgamma();
+    let (_, ln_n_k_factorial) = (n - k + 1.0).lgamma();
+    ln_n_factorial - ln_k_factorial - ln_n_k_factorial
+}
+
+/// The logarithm of the beta function, `ln B(a, b)`.
+pub fn ln_beta(a: f64, b: f64) -> f64 {
+    let (_, ln_gamma_a) = a.lgamma();
+    let (_, ln_gamma_b) = b.lgamma();
+    let (_, ln_gamma_ab) = (a + b).lgamma();
+    ln_gamma_a + ln_gamma_b - ln_gamma_ab
+}
+
+/// The continued fraction expansion of the incomplete beta function,
+/// evaluated with the modified Lentz's method, which converges
+/// quickly for `x < (a + 1) / (a + b + 2)`.
+fn beta_fraction(a: f64, b: f64, x: f64) -> f64 {
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY { d = TINY; }
+    d = 1.0 / d;
+    let mut h = d;
+    for m in range(1, MAX_ITERATIONS) {
+        let m = m as f64;
+        let m2 = 2.0 * m;
+
+        // the even step of the recurrence
+        let numer = m * (b - m) * x / ((a - 1.0 + m2) * (a + m2));
+        d = 1.0 + numer * d;
+        if d.abs() < TINY { d = TINY; }
+        c = 1.0 + numer / c;
+        if c.abs() < TINY { c = TINY; }
+        d = 1.0 / d;
+        h *= d * c;
+
+        // the odd step
+        let numer = -(a + m) * (a + b + m) * x / ((a + m2) * (a + 1.0 + m2));
+        d = 1.0 + numer * d;
+        if d.abs() < TINY { d = TINY; }
+        c = 1.0 + numer / c;
+        if c.abs() < TINY { c = TINY; }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    h
+}
+
+/// The regularised incomplete beta function, `I_x(a, b) = B(x; a, b)
+/// / B(a, b)`, for `a, b > 0`.
+pub fn beta_inc(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    } else if x >= 1.0 {
+        return 1.0;
+    }
+
+    let prefactor = num::exp(a * num::ln(x) + b * (-x).ln_1p() - ln_beta(a, b));
+    if x < (a + 1.0) / (a + b + 2.0) {
+        prefactor * beta_fraction(a, b, x) / a
+    } else {
+        // use the symmetry I_x(a, b) = 1 - I_(1-x)(b, a), so that the
+        // continued fraction converges quickly.
+        1.0 - prefactor * beta_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// Find `x` in `[low, high]` such that `cdf(x) == p`, starting from
+/// the guess `x`, using Newton's method with the density `pdf`, and
+/// falling back to bisection whenever a Newton step would leave the
+/// bracketing interval. `cdf(low) <= p <= cdf(high)` must hold.
+pub fn invert_cdf(p: f64, low: f64, high: f64, x: f64,
+                  cdf: &fn(f64) -> f64, pdf: &fn(f64) -> f64) -> f64 {
+    let mut low = low;
+    let mut high = high;
+    let mut x = x;
+    for _ in range(0, MAX_ITERATIONS) {
+        let error = cdf(x) - p;
+        if error == 0.0 {
+            return x;
+        }
+        if error < 0.0 { low = x; } else { high = x; }
+
+        let mut next = x - error / pdf(x);
+        // this also catches `next` being NaN, from a zero density.
+        if !(low < next && next < high) {
+            next = 0.5 * (low + high);
+        }
+        if (next - x).abs() <= EPSILON * next.abs() {
+            return next;
+        }
+        x = next;
+    }
+    x
+}
+
+/// Find the smallest `k` such that `cdf(k) >= p`, for a discrete
+/// distribution on the non-negative integers, by stepping from the
+/// initial guess `k`, which should be close to the answer.
+pub fn search_discrete_quantile(p: f64, k: u64, cdf: &fn(u64) -> f64) -> u64 {
+    let mut k = k;
+    if cdf(k) >= p {
+        while k > 0 && cdf(k - 1) >= p {
+            k -= 1;
+        }
+    } else {
+        while cdf(k) < p {
+            k += 1;
+        }
+    }
+    k
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::num;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() <= 1e-13 * expected.abs(),
+                "expected {}, got {}", expected, actual);
+    }
+
+    #[test]
+    fn test_gamma_p() {
+        // P(1, x) is the CDF of an Exp(1).
+        assert_close(gamma_p(1.0, 0.5), 1.0 - num::exp(-0.5));
+        assert_close(gamma_p(1.0, 5.0), 1.0 - num::exp(-5.0));
+        assert_close(gamma_p(2.0, 2.0), 1.0 - 3.0 * num::exp(-2.0));
+        assert_eq!(gamma_p(3.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_gamma_q() {
+        assert_close(gamma_q(1.0, 30.0), num::exp(-30.0));
+        assert_close(gamma_q(2.0, 40.0), 41.0 * num::exp(-40.0));
+        assert_eq!(gamma_q(3.0, 0.0), 1.0);
+    }
+
+    #[test]
+    fn test_beta_inc() {
+        // the CDF of a Beta(2, 3) is 6x^2 - 8x^3 + 3x^4.
+        assert_close(beta_inc(2.0, 3.0, 0.4), 0.5248);
+        // the CDF of a Beta(1/2, 1/2) is 2/pi asin(sqrt(x)).
+        assert_close(beta_inc(0.5, 0.5, 0.25), 1.0 / 3.0);
+        assert_close(beta_inc(0.5, 0.5, 0.75), 2.0 / 3.0);
+        assert_eq!(beta_inc(2.0, 2.0, 0.0), 0.0);
+        assert_eq!(beta_inc(2.0, 2.0, 1.0), 1.0);
+    }
+
+    #[test]
+    fn test_erf() {
+        // reference values computed to double precision.
+        assert_eq!(erf(0.0), 0.0);
+        assert_close(erf(0.1), 0.1124629160182849);
+        assert_close(erf(0.5), 0.5204998778130465);
+        assert_close(erf(1.0), 0.8427007929497149);
+        assert_close(erf(-1.0), -0.8427007929497149);
+        assert_close(erf(2.0), 0.9953222650189527);
+
+        assert_close(erfc(0.5), 0.4795001221869535);
+        assert_close(erfc(2.0), 0.004677734981047266);
+        assert_close(erfc(5.0), 1.5374597944280349e-12);
+        assert_close(erfc(10.0), 2.088487583762545e-45);
+        assert_close(erfc(-1.0), 1.842700792949715);
+    }
+
+    #[test]
+    fn test_std_normal_quantile() {
+        let table = [(0.5, 0.0),
+                     (0.75, 0.6744897501960817),
+                     (0.975, 1.959963984540054),
+                     (0.999, 3.090232306167814),
+                     (0.025, -1.959963984540054),
+                     (1e-10, -6.361340902404056),
+                     (1e-300, -37.0470962993612)];
+        for &(p, x) in table.iter() {
+            let actual = std_normal_quantile(p);
+            assert!((actual - x).abs() <= 1e-14 * (1.0 + x.abs()),
+                    "quantile({}): expected {}, got {}", p, x, actual);
+        }
+    }
+}
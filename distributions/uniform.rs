@@ -0,0 +1,350 @@
+use Rng;
+use Closed01;
+use super::Sample;
+use super::{ParameterError, unwrap_params};
+use std::num::Bounded;
+
+/// The uniform distribution over the half-open range [`low`, `high`),
+/// or the closed range [`low`, `high`] if constructed with
+/// `new_inclusive`.
+///
+/// For integers this gives each value in the range exactly equal
+/// probability (assuming the RNG is itself uniform), by rejecting the
+/// generated values that would cause a naive modulo operation to be
+/// biased; the size of this rejection zone is computed once, when the
+/// distribution is constructed. For floats in a half-open range the
+/// result is strictly less than `high`, even when rounding would
+/// otherwise produce it.
+///
+/// # Example
+///
+/// ~~~ {.rust}
+/// use rand::distributions::{Uniform, Sample};
+///
+/// fn main() {
+///     let between = Uniform::new(10u, 10000u);
+///     let mut rng = rand::task_rng();
+///     let mut sum = 0;
+///     for _ in range(0, 1000) {
+///         sum += between.sample(rng);
+///     }
+///     println!("{}", sum);
+/// }
+/// ~~~
+pub struct Uniform<X> {
+    priv low: X,
+    priv high: X,
+    priv range: X,
+    // the values generated by the RNG below which samples are
+    // accepted (integers only).
+    priv accept_zone: X,
+    // whether `high` itself can be generated (floats only; integer
+    // ranges are converted to half-open ones).
+    priv inclusive: bool
+}
+
+impl<X: SampleRange> Uniform<X> {
+    /// Construct a `Uniform` over [`low`, `high`). Fails if `low >=
+    /// high`, or, for floats, if the range is not finite.
+    pub fn new(low: X, high: X) -> Uniform<X> {
+        unwrap_params("Uniform", Uniform::try_new(low, high))
+    }
+
+    /// Construct a `Uniform` over [`low`, `high`), returning an error
+    /// if `low >= high`, or, for floats, if the range is not finite.
+    pub fn try_new(low: X, high: X) -> Result<Uniform<X>, ParameterError> {
+        SampleRange::construct_range(low, high)
+    }
+
+    /// Construct a `Uniform` over [`low`, `high`]. Fails if `low >
+    /// high`, or, for floats, if the range is not finite.
+    ///
+    /// Unlike `new`, this can cover the whole domain of an integer
+    /// type, e.g. `Uniform::new_inclusive(0u8, 255)`.
+    pub fn new_inclusive(low: X, high: X) -> Uniform<X> {
+        unwrap_params("Uniform", Uniform::try_new_inclusive(low, high))
+    }
+
+    /// Construct a `Uniform` over [`low`, `high`], returning an error
+    /// if `low > high`, or, for floats, if the range is not finite.
+    pub fn try_new_inclusive(low: X, high: X) -> Result<Uniform<X>, ParameterError> {
+        SampleRange::construct_range_inclusive(low, high)
+    }
+}
+
+impl<X: SampleRange> Sample<X> for Uniform<X> {
+    fn sample<R: Rng>(&self, rng: &mut R) -> X {
+        SampleRange::sample_range(self, rng)
+    }
+}
+
+/// The helper trait for types that have a sensible way to sample
+/// uniformly between two values. This should not be used directly,
+/// and is only to facilitate `Uniform`.
+pub trait SampleRange {
+    /// Construct the `Uniform` object that `sample_range`
+    /// requires. This should not ever be called directly, only via
+    /// `Uniform::try_new`, which returns an error if `low >= high`.
+    fn construct_range(low: Self, high: Self) -> Result<Uniform<Self>, ParameterError>;
+
+    /// Construct the `Uniform` object for the closed range [`low`,
+    /// `high`]. This should not ever be called directly, only via
+    /// `Uniform::try_new_inclusive`, which returns an error if `low >
+    /// high`.
+    fn construct_range_inclusive(low: Self, high: Self) -> Result<Uniform<Self>, ParameterError>;
+
+    /// Sample a value from the given `Uniform` with the given `Rng` as
+    /// a source of randomness.
+    fn sample_range<R: Rng>(r: &Uniform<Self>, rng: &mut R) -> Self;
+}
+
+macro_rules! integer_impl {
+    ($ty:ty, $unsigned:ty) => {
+        impl SampleRange for $ty {
+            // we play free and fast with unsigned vs signed here
+            // (when $ty is signed), but that's fine, since the
+            // contract of this macro is for $ty and $unsigned to be
+            // "bit-equal", so casting between them is a no-op & a
+            // bijection.
+
+            fn construct_range(low: $ty, high: $ty) -> Result<Uniform<$ty>, ParameterError> {
+                if !(low < high) {
+                    return Err(ParameterError::new("high", "must be greater than low"));
+                }
+                SampleRange::construct_range_inclusive(low, high - 1)
+            }
+
+            fn construct_range_inclusive(low: $ty,
+                                         high: $ty) -> Result<Uniform<$ty>, ParameterError> {
+                if !(low <= high) {
+                    return Err(ParameterError::new("high", "must be at least low"));
+                }
+                // this wraps to 0 when the range covers the whole
+                // type, which `sample_range` handles specially.
+                let range = (high as $unsigned - low as $unsigned) + 1;
+                let unsigned_max: $unsigned = Bounded::max_value();
+
+                // this is the largest number that fits into $unsigned
+                // that `range` divides evenly, so, if we've sampled
+                // `n` uniformly from this region, then `n % range` is
+                // uniform in [0, range)
+                let zone = if range == 0 { 0 } else { unsigned_max - unsigned_max % range };
+
+                Ok(Uniform {
+                    low: low,
+                    high: high,
+                    range: range as $ty,
+                    accept_zone: zone as $ty,
+                    inclusive: true
+                })
+            }
+
+            #[inline]
+            fn sample_range<R: Rng>(r: &Uniform<$ty>, rng: &mut R) -> $ty {
+                if r.range == 0 {
+                    // every value is equally likely.
+                    return rng.gen();
+                }
+                loop {
+                    // rejection sample
+                    let v = rng.gen::<$unsigned>();
+                    // until we find something that fits into the
+                    // region which r.range evenly divides (this will
+                    // be uniformly distributed)
+                    if v < r.accept_zone as $unsigned {
+                        // and return it, with some adjustments
+                        return r.low + (v % r.range as $unsigned) as $ty;
+                    }
+                }
+            }
+        }
+    }
+}
+
+integer_impl! { i8, u8 }
+integer_impl! { i16, u16 }
+integer_impl! { i32, u32 }
+integer_impl! { i64, u64 }
+integer_impl! { int, uint }
+integer_impl! { u8, u8 }
+integer_impl! { u16, u16 }
+integer_impl! { u32, u32 }
+integer_impl! { u64, u64 }
+integer_impl! { uint, uint }
+
+macro_rules! float_impl {
+    ($ty:ty) => {
+        impl SampleRange for $ty {
+            fn construct_range(low: $ty, high: $ty) -> Result<Uniform<$ty>, ParameterError> {
+                if !(low < high) {
+                    return Err(ParameterError::new("high", "must be greater than low"));
+                }
+                do SampleRange::construct_range_inclusive(low, high).map |r| {
+                    Uniform { inclusive: false, ..r }
+                }
+            }
+
+            fn construct_range_inclusive(low: $ty,
+                                         high: $ty) -> Result<Uniform<$ty>, ParameterError> {
+                if !(low <= high) {
+                    return Err(ParameterError::new("high", "must be at least low"));
+                }
+                let range = high - low;
+                if !range.is_finite() {
+                    return Err(ParameterError::not_finite("high - low"));
+                }
+                Ok(Uniform {
+                    low: low,
+                    high: high,
+                    range: range,
+                    accept_zone: 0.0,
+                    inclusive: true
+                })
+            }
+
+            fn sample_range<R: Rng>(r: &Uniform<$ty>, rng: &mut R) -> $ty {
+                if r.inclusive {
+                    loop {
+                        let Closed01(u) = rng.gen::<Closed01<$ty>>();
+                        let x = r.low + r.range * u;
+                        if x <= r.high {
+                            return x;
+                        }
+                    }
+                }
+                loop {
+                    // `low + range * x` can round up to `high` for `x`
+                    // very close to 1, so retry when that happens.
+                    let x = r.low + r.range * rng.gen::<$ty>();
+                    if x < r.high {
+                        return x;
+                    }
+                }
+            }
+        }
+    }
+}
+
+float_impl! { f32 }
+float_impl! { f64 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use distributions::Sample;
+    use std::num::Bounded;
+
+    #[test]
+    fn test_integers() {
+        let mut rng = ::rng();
+        macro_rules! t (
+            ($($ty:ty),*) => {{
+                $(
+                   let v: &[($ty, $ty)] = [(0, 10),
+                                           (10, 127),
+                                           (Bounded::min_value(), Bounded::max_value())];
+                   for &(low, high) in v.iter() {
+                        let sampler: Uniform<$ty> = Uniform::new(low, high);
+                        for _ in range(0, 1000) {
+                            let v = sampler.sample(&mut rng);
+                            assert!(low <= v && v < high);
+                        }
+                    }
+                 )*
+            }}
+        );
+        t!(i8, i16, i32, i64, int,
+           u8, u16, u32, u64, uint)
+    }
+
+    #[test]
+    fn test_integer_unbiased() {
+        // 0..170 is the classic example of modulo bias for u8: the
+        // values below 86 would be twice as likely as the rest.
+        let mut rng = ::rng();
+        let sampler = Uniform::new(0u8, 170);
+        let mut low_count = 0;
+        for _ in range(0, 10000) {
+            if sampler.sample(&mut rng) < 85 {
+                low_count += 1;
+            }
+        }
+        // the expected count is 5000, with standard deviation 50.
+        assert!(4700 < low_count && low_count < 5300, "low_count = {}", low_count);
+    }
+
+    #[test]
+    fn test_floats() {
+        let mut rng = ::rng();
+        macro_rules! t (
+            ($($ty:ty),*) => {{
+                $(
+                   let v: &[($ty, $ty)] = [(0.0, 100.0),
+                                           (-1e35, -1e25),
+                                           (1e-35, 1e-25),
+                                           (-1e35, 1e35)];
+                   for &(low, high) in v.iter() {
+                        let sampler: Uniform<$ty> = Uniform::new(low, high);
+                        for _ in range(0, 1000) {
+                            let v = sampler.sample(&mut rng);
+                            assert!(low <= v && v < high);
+                        }
+                    }
+                 )*
+            }}
+        );
+
+        t!(f32, f64)
+    }
+
+    #[test]
+    fn test_inclusive() {
+        let mut rng = ::rng();
+        // the whole domain.
+        let sampler = Uniform::new_inclusive(0u64, Bounded::max_value());
+        sampler.sample(&mut rng);
+        let sampler = Uniform::new_inclusive(-128i8, 127);
+        let mut seen = [false, .. 256];
+        for _ in range(0, 10000) {
+            seen[(sampler.sample(&mut rng) as int + 128) as uint] = true;
+        }
+        assert!(seen.iter().all(|&x| x));
+
+        // both endpoints are possible.
+        let sampler = Uniform::new_inclusive(3u8, 4);
+        let mut seen = [false, .. 2];
+        for _ in range(0, 100) {
+            seen[sampler.sample(&mut rng) - 3] = true;
+        }
+        assert_eq!(seen.to_owned(), ~[true, true]);
+
+        let sampler = Uniform::new_inclusive(5i, 5);
+        assert_eq!(sampler.sample(&mut rng), 5);
+        let sampler = Uniform::new_inclusive(1.5f64, 1.5);
+        assert_eq!(sampler.sample(&mut rng), 1.5);
+        let sampler = Uniform::new_inclusive(-1f32, 1.0);
+        for _ in range(0, 1000) {
+            let v = sampler.sample(&mut rng);
+            assert!(-1.0 <= v && v <= 1.0);
+        }
+
+        assert!(Uniform::try_new_inclusive(2, 1).is_err());
+        assert!(Uniform::try_new_inclusive(2.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_try_new() {
+        assert!(Uniform::try_new(10, 10).is_err());
+        assert!(Uniform::try_new(10u8, 5).is_err());
+        assert!(Uniform::try_new(1.0, 0.0).is_err());
+        assert!(Uniform::try_new(0.0, Float::NaN()).is_err());
+        assert!(Uniform::try_new(-1e308, 1e308).is_err());
+        assert!(Uniform::try_new(-1i8, 0).is_ok());
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_new_invalid() {
+        Uniform::new(10, 10);
+    }
+}
@@ -0,0 +1,133 @@
+use Rng;
+use super::Sample;
+use super::{ParameterError, unwrap_params};
+use std::vec;
+
+/// A distribution over the indices `0, 1, ..., n - 1` of a list of `n`
+/// non-negative weights, choosing each index with probability
+/// proportional to its weight.
+///
+/// This uses the alias method of A. J. Walker, with the numerically
+/// stable table construction of M. D. Vose, [*A Linear Algorithm for
+/// Generating Random Numbers with a Given
+/// Distribution*](http://dx.doi.org/10.1109/32.92917) (1991). The
+/// table takes `O(n)` time to build, and each sample takes constant
+/// time.
+pub struct WeightedIndex {
+    // the probability of keeping each column, rather than switching to
+    // its alias.
+    priv prob: ~[f64],
+    priv alias: ~[uint]
+}
+
+impl WeightedIndex {
+    /// Construct a `WeightedIndex` for the weights `weights`. Fails if
+    /// there are no weights, any are negative, infinite or NaN, or
+    /// they are all zero.
+    pub fn new(weights: &[f64]) -> WeightedIndex {
+        unwrap_params("WeightedIndex", WeightedIndex::try_new(weights))
+    }
+
+    /// Construct a `WeightedIndex` for the weights `weights`, returning
+    /// an error if there are no weights, any are negative, infinite or
+    /// NaN, or they are all zero.
+    pub fn try_new(weights: &[f64]) -> Result<WeightedIndex, ParameterError> {
+        if weights.is_empty() {
+            return Err(ParameterError::new("weights", "must be non-empty"));
+        } else if !weights.iter().all(|&w| w >= 0.0 && w.is_finite()) {
+            return Err(ParameterError::new("weights", "must be non-negative and finite"));
+        }
+        let total = weights.iter().fold(0.0, |a, &b| a + b);
+        if total == 0.0 {
+            return Err(ParameterError::new("weights", "must not all be zero"));
+        } else if !total.is_finite() {
+            return Err(ParameterError::new("weights", "must have a finite sum"));
+        }
+
+        // scale the weights so that the average is 1, and split them
+        // into those that underfill and overfill their column.
+        let n = weights.len();
+        let mut prob: ~[f64] = weights.iter().map(|&w| w * n as f64 / total).collect();
+        let mut alias = vec::from_elem(n, 0u);
+        let mut small = vec::with_capacity(n);
+        let mut large = vec::with_capacity(n);
+        for (i, &p) in prob.iter().enumerate() {
+            if p < 1.0 { small.push(i) } else { large.push(i) }
+        }
+
+        // fill each underfull column with the excess of an overfull
+        // one, which may in turn become underfull.
+        while !small.is_empty() && !large.is_empty() {
+            let s = small.pop();
+            let l = large.pop();
+            alias[s] = l;
+            prob[l] = (prob[l] + prob[s]) - 1.0;
+            if prob[l] < 1.0 { small.push(l) } else { large.push(l) }
+        }
+
+        // anything left over is exactly full, up to rounding errors.
+        for &i in small.iter().chain(large.iter()) {
+            prob[i] = 1.0;
+        }
+
+        Ok(WeightedIndex { prob: prob, alias: alias })
+    }
+}
+
+impl Sample<uint> for WeightedIndex {
+    fn sample<R: Rng>(&self, rng: &mut R) -> uint {
+        let column = rng.gen_range(0u, self.prob.len());
+        if rng.gen::<f64>() < self.prob[column] {
+            column
+        } else {
+            self.alias[column]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use distributions::Sample;
+
+    #[test]
+    fn test_weighted_index() {
+        let mut rng = ::rng();
+        let weights = [1.0, 0.0, 2.0, 5.0, 0.5, 1.5];
+        let total = 10.0;
+        let dist = WeightedIndex::new(weights);
+
+        let mut counts = [0u, .. 6];
+        for _ in range(0, 10000) {
+            counts[dist.sample(&mut rng)] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        for (&count, &w) in counts.iter().zip(weights.iter()) {
+            // within 6 standard deviations of the expected count.
+            let p = w / total;
+            let expected = 10000.0 * p;
+            let sd = (10000.0 * p * (1.0 - p)).sqrt();
+            assert!((count as f64 - expected).abs() <= 6.0 * sd,
+                    "weight {}: got {}, expected {}", w, count, expected);
+        }
+    }
+
+    #[test]
+    fn test_weighted_index_single() {
+        let mut rng = ::rng();
+        let dist = WeightedIndex::new([3.0]);
+        for _ in range(0, 100) {
+            assert_eq!(dist.sample(&mut rng), 0);
+        }
+    }
+
+    #[test]
+    fn test_weighted_index_try_new() {
+        assert!(WeightedIndex::try_new([]).is_err());
+        assert!(WeightedIndex::try_new([0.0, 0.0]).is_err());
+        assert!(WeightedIndex::try_new([1.0, -1.0]).is_err());
+        assert!(WeightedIndex::try_new([1.0, Float::NaN()]).is_err());
+        assert!(WeightedIndex::try_new([1.0, Float::infinity()]).is_err());
+        assert!(WeightedIndex::try_new([0.0, 1.0]).is_ok());
+    }
+}
@@ -17,6 +17,11 @@ suffice, but sometimes an annotation is required, e.g. `rand::random::<float>()`
 See the `distributions` submodule for sampling random numbers from
 distributions like normal and exponential.
 
+Random floats generated by `gen` are in the half-open interval [0,
+1); the `Open01`, `Closed01` and `FullPrecision` wrapper types can be
+generated instead for the other intervals, or for values with more
+precision near 0.
+
 # Task-local RNG
 
 There is built-in support for a RNG associated with each task stored
@@ -60,7 +65,9 @@ fn main () {
 #[cfg(test)]
 extern mod extra;
 
-use std::{str, u64, u32, vec, local_data, os};
+use std::{str, vec, local_data, os};
+use std::num::CheckedAdd;
+use std::hashmap::HashSet;
 
 #[path="rng/mod.rs"]
 pub mod rng;
@@ -68,6 +75,15 @@ pub mod rng;
 #[path="distributions/mod.rs"]
 pub mod distributions;
 
+#[path="reservoir.rs"]
+pub mod reservoir;
+
+#[path="shuffle.rs"]
+pub mod shuffle;
+
+use distributions::{Sample, Uniform, SampleRange};
+use reservoir::{ReservoirSampler, WeightedReservoirSampler};
+
 /// Controls how the task-local RNG is reseeded.
 enum TaskRngReseeder {
     /// Reseed using the standard Rng::new() function.
@@ -244,8 +260,100 @@ pub trait Rand {
     }
 }
 
-static SCALE_32: f32 = ((u32::max_value as f32) + 1.0f32);
-static SCALE_64: f64 = ((u64::max_value as f64) + 1.0f64);
+/// The broad categories of failure of a source of randomness.
+#[deriving(Eq, Clone)]
+pub enum ErrorKind {
+    /// The source is not available, and will never be, e.g. the
+    /// device could not be opened.
+    Unavailable,
+    /// The source is temporarily unable to produce randomness, and
+    /// retrying later may succeed, e.g. the hardware RNG has been
+    /// exhausted.
+    NotReady,
+    /// Any other failure, e.g. a read returned too few bytes.
+    Unexpected
+}
+
+/// The error returned by the fallible `try_` methods of `Rng`, and by
+/// the constructors of RNGs that read from external sources.
+#[deriving(Eq, Clone)]
+pub struct Error {
+    /// The category of the failure.
+    kind: ErrorKind,
+    /// A description of what failed, e.g. `"error opening
+    /// /dev/urandom: ..."`.
+    desc: ~str
+}
+
+impl Error {
+    /// Create an error of kind `kind`, described by `desc`.
+    pub fn new(kind: ErrorKind, desc: ~str) -> Error {
+        Error { kind: kind, desc: desc }
+    }
+}
+
+impl ToStr for Error {
+    fn to_str(&self) -> ~str {
+        let kind = match self.kind {
+            Unavailable => "RNG unavailable",
+            NotReady => "RNG not ready",
+            Unexpected => "RNG error"
+        };
+        format!("{}: {}", kind, self.desc)
+    }
+}
+
+/// Unwrap the result of a fallible RNG operation, failing the task
+/// with a description of the error.
+fn unwrap_rng<T>(result: Result<T, Error>) -> T {
+    match result {
+        Ok(x) => x,
+        Err(e) => fail!("{}", e.to_str())
+    }
+}
+
+/// Write the `size` low bytes of `word` into the start of `dest` in
+/// little-endian order, stopping early if `dest` is shorter. Returns
+/// the number of bytes written.
+#[inline]
+fn write_le_bytes(dest: &mut [u8], word: u64, size: uint) -> uint {
+    let n = if dest.len() < size { dest.len() } else { size };
+    let mut word = word;
+    for b in dest.mut_slice_to(n).mut_iter() {
+        *b = word as u8;
+        word >>= 8;
+    }
+    n
+}
+
+/// Shuffle `values` in place with the Fisher-Yates algorithm, using
+/// `rng` as the source of randomness. If `cyclic` is true, this uses
+/// Sattolo's variant instead, which only generates permutations
+/// consisting of a single cycle.
+///
+/// `accept` is called with each position and the element as it is
+/// locked in place; if it returns false, the shuffle stops early,
+/// leaving `values` in an unspecified order, and this returns false.
+fn fisher_yates<T, R: Rng>(rng: &mut R, values: &mut [T], cyclic: bool,
+                           accept: &fn(uint, &T) -> bool) -> bool {
+    let mut i = values.len();
+    while i >= 2u {
+        // invariant: elements with index >= i have been locked in place.
+        i -= 1u;
+        // lock element i in place; Sattolo's algorithm never leaves an
+        // element where it is, so that it joins the cycle.
+        let j = if cyclic { rng.gen_range(0u, i) } else { rng.gen_range(0u, i + 1u) };
+        values.swap(i, j);
+        if !accept(i, &values[i]) {
+            return false;
+        }
+    }
+    if i == 1 {
+        accept(0, &values[0])
+    } else {
+        true
+    }
+}
 
 /// A random number generator.
 ///
@@ -290,10 +398,15 @@ pub trait Rng {
         2 * self.entropy_u32()
     }
 
-    /// Return the next random f32.
+    /// Return the next random f32, in the range [0, 1).
+    ///
+    /// The default implementation uses the top 24 bits of `next_u32`
+    /// as the mantissa, so the result is a multiple of 2^-24 and is
+    /// never 1.0. See `FullPrecision` for a version that can generate
+    /// every representable value in [0, 1).
     #[inline]
     fn next_f32(&mut self) -> f32 {
-        (self.next_u32() as f32) / SCALE_32
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
     }
 
     /// The maximum number of bytes of entropy consumed to produce a
@@ -303,10 +416,15 @@ pub trait Rng {
         self.entropy_u32()
     }
 
-    /// Return the next random f64.
+    /// Return the next random f64, in the range [0, 1).
+    ///
+    /// The default implementation uses the top 53 bits of `next_u64`
+    /// as the mantissa, so the result is a multiple of 2^-53 and is
+    /// never 1.0. See `FullPrecision` for a version that can generate
+    /// every representable value in [0, 1).
     #[inline]
     fn next_f64(&mut self) -> f64 {
-        (self.next_u64() as f64) / SCALE_64
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
     }
 
     /// The maximum number of bytes of entropy consumed to produce a
@@ -316,6 +434,68 @@ pub trait Rng {
         self.entropy_u64()
     }
 
+    /// Fill `dest` with random data.
+    ///
+    /// The default implementation writes the results of `next_u64`
+    /// into `dest` in little-endian order, 8 bytes at a time,
+    /// discarding any bytes left over at the end. RNGs that generate
+    /// their output in blocks should override this to copy from their
+    /// buffer directly.
+    ///
+    /// # Example
+    ///
+    /// ~~~ {.rust}
+    /// use std::rand;
+    ///
+    /// fn main() {
+    ///    let mut v = [0u8, .. 13579];
+    ///    rand::task_rng().fill_bytes(v);
+    ///    printfln!(v);
+    /// }
+    /// ~~~
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let mut i = 0;
+        while i < dest.len() {
+            i += write_le_bytes(dest.mut_slice_from(i), self.next_u64(), 8);
+        }
+    }
+
+    /// The maximum number of bytes of entropy consumed to fill `len`
+    /// bytes via `fill_bytes`.
+    #[inline]
+    fn entropy_bytes(&self, len: uint) -> uint {
+        (len + 7) / 8 * self.entropy_u64()
+    }
+
+    /// Return the next random u32, or an error if the underlying
+    /// source of randomness failed.
+    ///
+    /// Generators that cannot fail need not implement this: the
+    /// default just calls `next_u32`. Those that can (such as those
+    /// reading from the operating system or hardware) should
+    /// implement this, and have `next_u32` fail the task on error.
+    #[inline]
+    fn try_next_u32(&mut self) -> Result<u32, Error> {
+        Ok(self.next_u32())
+    }
+
+    /// Return the next random u64, or an error if the underlying
+    /// source of randomness failed. See `try_next_u32`.
+    #[inline]
+    fn try_next_u64(&mut self) -> Result<u64, Error> {
+        Ok(self.next_u64())
+    }
+
+    /// Fill `dest` with random data, or return an error if the
+    /// underlying source of randomness failed. See `try_next_u32`.
+    ///
+    /// The contents of `dest` are unspecified if this fails.
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+
     /// Return a random value of a Rand type.
     ///
     /// # Example
@@ -356,14 +536,19 @@ pub trait Rng {
         Rand::rand_vec(self, len)
     }
 
-    /// Generate a random primitive integer in the range [`low`,
-    /// `high`). Fails if `low >= high`.
+    /// Generate a random value in the range [`low`, `high`). Fails if
+    /// `low >= high`.
+    ///
+    /// This works for all primitive integer types and for `f32` and
+    /// `f64`, via `distributions::Uniform`. For integers it gives a
+    /// uniform distribution (assuming this RNG is itself uniform),
+    /// even for edge cases like `gen_range(0u8, 170)`, which a naive
+    /// modulo operation would return numbers less than 85 with double
+    /// the probability to those greater than 85. For floats the result
+    /// is always strictly less than `high`.
     ///
-    /// This gives a uniform distribution (assuming this RNG is itself
-    /// uniform), even for edge cases like `gen_integer_range(0u8,
-    /// 170)`, which a naive modulo operation would return numbers
-    /// less than 85 with double the probability to those greater than
-    /// 85.
+    /// If sampling many values from the same range, constructing a
+    /// `Uniform` once is more efficient.
     ///
     /// # Example
     ///
@@ -372,27 +557,93 @@ pub trait Rng {
     ///
     /// fn main() {
     ///    let rng = rand::task_rng();
-    ///    let n: uint = rng.gen_integer_range(0u, 10);
+    ///    let n: uint = rng.gen_range(0u, 10);
     ///    printfln!(n);
-    ///    let m: i16 = rng.gen_integer_range(-40, 400);
+    ///    let m: f64 = rng.gen_range(-40.0, 1.3e5);
     ///    printfln!(m);
     /// }
     /// ~~~
-    fn gen_integer_range<T: Rand + Int>(&mut self, low: T, high: T) -> T {
-        assert!(low < high, "RNG.gen_range called with low >= high");
-        let range = (high - low).to_u64().unwrap();
-        let accept_zone = u64::max_value - u64::max_value % range;
-        loop {
-            let rand = self.gen::<u64>();
-            if rand < accept_zone {
-                return low + NumCast::from(rand % range).unwrap();
-            }
-        }
+    fn gen_range<T: SampleRange + Ord>(&mut self, low: T, high: T) -> T {
+        assert!(low < high, "Rng.gen_range called with low >= high");
+        Uniform::new(low, high).sample(self)
+    }
+
+    /// Generate a random value in the closed range [`low`, `high`].
+    /// Fails if `low > high`.
+    ///
+    /// Unlike `gen_range`, this can generate any value of an integer
+    /// type, e.g. `gen_range_inclusive(0u64, u64::max_value)`, and has
+    /// the same guarantee of uniformity.
+    ///
+    /// # Example
+    ///
+    /// ~~~ {.rust}
+    /// use std::rand;
+    ///
+    /// fn main() {
+    ///    let rng = rand::task_rng();
+    ///    let die: uint = rng.gen_range_inclusive(1u, 6);
+    ///    printfln!(die);
+    ///    let byte: u8 = rng.gen_range_inclusive(0u8, 255);
+    ///    printfln!(byte);
+    /// }
+    /// ~~~
+    fn gen_range_inclusive<T: SampleRange + Ord>(&mut self, low: T, high: T) -> T {
+        assert!(low <= high, "Rng.gen_range_inclusive called with low > high");
+        Uniform::new_inclusive(low, high).sample(self)
+    }
+
+    /// Generate a random primitive integer in the range [`low`,
+    /// `high`). Fails if `low >= high`.
+    ///
+    /// This is equivalent to `gen_range`, which should be preferred.
+    fn gen_integer_range<T: SampleRange + Int>(&mut self, low: T, high: T) -> T {
+        self.gen_range(low, high)
+    }
+
+    /// Return a bool with a 1 in `n` chance of being true. This is
+    /// always true if `n` is 0 or 1.
+    ///
+    /// # Example
+    ///
+    /// ~~~ {.rust}
+    /// use std::rand;
+    ///
+    /// fn main() {
+    ///     let rng = rand::task_rng();
+    ///     printfln!("%b", rng.gen_weighted_bool(3));
+    /// }
+    /// ~~~
+    fn gen_weighted_bool(&mut self, n: uint) -> bool {
+        n <= 1 || self.gen_range(0, n) == 0
+    }
+
+    /// Return a bool that is true with probability `p`. Fails if `p`
+    /// is not in [0, 1].
+    ///
+    /// # Example
+    ///
+    /// ~~~ {.rust}
+    /// use std::rand;
+    ///
+    /// fn main() {
+    ///     let rng = rand::task_rng();
+    ///     printfln!("%b", rng.gen_bool(0.25));
+    /// }
+    /// ~~~
+    fn gen_bool(&mut self, p: f64) -> bool {
+        assert!(0.0 <= p && p <= 1.0, "Rng.gen_bool called with p outside [0, 1]");
+        // `gen` is strictly less than 1, so this is always true for
+        // p == 1, and always false for p == 0.
+        self.gen::<f64>() < p
     }
 
     /// Return a random string of the specified length composed of
     /// A-Z,a-z,0-9.
     ///
+    /// See `distributions::CharsetDist` and `distributions::StringDist`
+    /// for other alphabets.
+    ///
     /// # Example
     ///
     /// ~~~ {.rust}
@@ -430,7 +681,150 @@ pub trait Rng {
         if values.is_empty() {
             None
         } else {
-            Some(&values[self.gen_integer_range(0u, values.len())])
+            Some(&values[self.gen_range(0u, values.len())])
+        }
+    }
+
+    /// Choose `Some(&mut item)` randomly, returning `None` if values
+    /// is empty.
+    ///
+    /// # Example
+    ///
+    /// ~~~ {.rust}
+    /// use std::rand;
+    ///
+    /// fn main() {
+    ///     let mut v = [1, 2, 3];
+    ///     *rand::task_rng().choose_mut(v).unwrap() = 10;
+    ///     printfln!(v);
+    /// }
+    /// ~~~
+    fn choose_mut<'a, T>(&mut self, values: &'a mut [T]) -> Option<&'a mut T> {
+        if values.is_empty() {
+            None
+        } else {
+            let i = self.gen_range(0u, values.len());
+            Some(&mut values[i])
+        }
+    }
+
+    /// Choose an item from `values`, where each item `(x, weight)` is
+    /// chosen with probability proportional to `weight`. Returns
+    /// `None` if `values` is empty or all the weights are 0. Fails if
+    /// the weights add up to more than `uint::max_value`.
+    ///
+    /// This takes time linear in the length of `values`; when making
+    /// many choices from the same weights,
+    /// `distributions::WeightedIndex` takes constant time per choice.
+    ///
+    /// # Example
+    ///
+    /// ~~~ {.rust}
+    /// use std::rand;
+    ///
+    /// fn main() {
+    ///     let rng = rand::task_rng();
+    ///     let x = [('a', 4u), ('b', 2), ('c', 1)];
+    ///     // 'a' will be chosen about 4 times in 7.
+    ///     printfln!(rng.choose_weighted(x));
+    /// }
+    /// ~~~
+    fn choose_weighted<'a, T>(&mut self, values: &'a [(T, uint)]) -> Option<&'a T> {
+        let mut total = 0u;
+        for &(_, weight) in values.iter() {
+            total = match total.checked_add(&weight) {
+                Some(t) => t,
+                None => fail!("Rng.choose_weighted called with weights totalling more than \
+                               uint::max_value")
+            };
+        }
+        if total == 0 {
+            return None;
+        }
+
+        let mut chosen = self.gen_range(0u, total);
+        for &(ref item, weight) in values.iter() {
+            if chosen < weight {
+                return Some(item);
+            }
+            chosen -= weight;
+        }
+        unreachable!()
+    }
+
+    /// Choose `k` distinct elements of `values` (that is, without
+    /// replacement), in no particular order. If `values` has fewer
+    /// than `k` elements, all of them are returned.
+    ///
+    /// # Example
+    ///
+    /// ~~~ {.rust}
+    /// use std::rand;
+    ///
+    /// fn main() {
+    ///     let rng = rand::task_rng();
+    ///     printfln!(rng.choose_multiple([1, 2, 3, 4, 5, 6], 3));
+    /// }
+    /// ~~~
+    fn choose_multiple<'a, T>(&mut self, values: &'a [T], k: uint) -> ~[&'a T] {
+        self.sample(values.iter(), k)
+    }
+
+    /// Choose `k` distinct indices from `0, 1, ..., n - 1` (that is,
+    /// without replacement), in an unspecified order. Fails if `k >
+    /// n`.
+    ///
+    /// Depending on how `k` compares to `n`, this uses a partial
+    /// Fisher-Yates shuffle of all `n` indices, Floyd's algorithm, or
+    /// rejection sampling with a hash set. The latter two take time
+    /// and memory proportional to `k` rather than `n`, so this is
+    /// efficient even for a huge `n`.
+    ///
+    /// # Example
+    ///
+    /// ~~~ {.rust}
+    /// use std::rand;
+    ///
+    /// fn main() {
+    ///     let rng = rand::task_rng();
+    ///     // 5 distinct indices, without allocating a billion of them.
+    ///     printfln!(rng.sample_indices(1_000_000_000, 5));
+    /// }
+    /// ~~~
+    fn sample_indices(&mut self, n: uint, k: uint) -> ~[uint] {
+        assert!(k <= n, "Rng.sample_indices called with k > n");
+
+        // the thresholds are rough estimates of where each method
+        // becomes faster than the others.
+        if k * 4 >= n {
+            // a large fraction of the indices is needed anyway, so
+            // shuffle just the first k of all of them.
+            let mut indices: ~[uint] = range(0, n).collect();
+            self.partial_shuffle(indices, k);
+            indices.truncate(k);
+            indices
+        } else if k <= 32 {
+            // Floyd's algorithm, which never rejects, but needs a
+            // linear search of the chosen indices at each step.
+            let mut indices = vec::with_capacity(k);
+            for j in range(n - k, n) {
+                let t = self.gen_range_inclusive(0, j);
+                let chosen = if indices.contains(&t) { j } else { t };
+                indices.push(chosen);
+            }
+            indices
+        } else {
+            // at most a quarter of the samples are rejected, since k
+            // is small compared to n.
+            let mut seen = HashSet::with_capacity(k);
+            let mut indices = vec::with_capacity(k);
+            while indices.len() < k {
+                let i = self.gen_range(0, n);
+                if seen.insert(i) {
+                    indices.push(i);
+                }
+            }
+            indices
         }
     }
 
@@ -468,12 +862,89 @@ pub trait Rng {
     /// }
     /// ~~~
     fn shuffle_mut<T>(&mut self, values: &mut [T]) {
-        let mut i = values.len();
-        while i >= 2u {
-            // invariant: elements with index >= i have been locked in place.
-            i -= 1u;
-            // lock element i in place.
-            values.swap(i, self.gen_integer_range(0u, i + 1u));
+        fisher_yates(self, values, false, |_, _| true);
+    }
+
+    /// Shuffle a mutable vector in place into a random cyclic order,
+    /// using Sattolo's algorithm: following each element's new
+    /// position from its old one visits every position exactly once
+    /// before returning to the start. Each of the `(n - 1)!` such
+    /// orders is equally likely, and no element stays where it was
+    /// (when there are at least two).
+    ///
+    /// # Example
+    ///
+    /// ~~~ {.rust}
+    /// use std::rand;
+    ///
+    /// fn main() {
+    ///    let rng = rand::task_rng();
+    ///    // a random linked list through 0, 1, ..., 4: node i points
+    ///    // to next[i].
+    ///    let mut next = [0u, 1, 2, 3, 4];
+    ///    rng.shuffle_cyclic(next);
+    ///    printfln!(next);
+    /// }
+    /// ~~~
+    fn shuffle_cyclic<T>(&mut self, values: &mut [T]) {
+        fisher_yates(self, values, true, |_, _| true);
+    }
+
+    /// Return a uniformly random derangement of `0, 1, ..., n - 1`,
+    /// that is, a permutation `p` with `p[i] != i` for every `i`. Fails
+    /// if `n == 1`, since there are no such permutations.
+    ///
+    /// This shuffles repeatedly until it finds a derangement, stopping
+    /// each attempt as soon as a fixed point is locked in place; about
+    /// `e` attempts are needed on average.
+    ///
+    /// # Example
+    ///
+    /// ~~~ {.rust}
+    /// use std::rand;
+    ///
+    /// fn main() {
+    ///    // a secret santa draw: person i gives a gift to santa[i].
+    ///    let santa = rand::task_rng().random_derangement(5);
+    ///    printfln!(santa);
+    /// }
+    /// ~~~
+    fn random_derangement(&mut self, n: uint) -> ~[uint] {
+        assert!(n != 1, "Rng.random_derangement called with n == 1");
+        loop {
+            let mut perm: ~[uint] = range(0, n).collect();
+            if fisher_yates(self, perm, false, |i, &x| i != x) {
+                return perm;
+            }
+        }
+    }
+
+    /// Shuffle the first `k` elements of a mutable vector in place,
+    /// so that they are a uniformly random choice of `k` of its
+    /// elements, in a random order. The remaining elements are left
+    /// in an unspecified order. This takes time proportional to `k`.
+    /// Fails if `k > values.len()`.
+    ///
+    /// See `shuffle::ShuffleIter` for a lazy version.
+    ///
+    /// # Example
+    ///
+    /// ~~~ {.rust}
+    /// use std::rand;
+    ///
+    /// fn main() {
+    ///    let rng = rand::task_rng();
+    ///    let mut y = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+    ///    rng.partial_shuffle(y, 3);
+    ///    printfln!(y.slice_to(3));
+    /// }
+    /// ~~~
+    fn partial_shuffle<T>(&mut self, values: &mut [T], k: uint) {
+        let n = values.len();
+        assert!(k <= n, "Rng.partial_shuffle called with k > values.len()");
+        for i in range(0, k) {
+            // invariant: elements with index < i have been locked in place.
+            values.swap(i, self.gen_range(i, n));
         }
     }
 
@@ -494,7 +965,12 @@ pub trait Rng {
         RandIterator::new(self)
     }
 
-    /// Randomly sample up to `n` elements from an iterator.
+    /// Randomly sample up to `n` elements from an iterator, without
+    /// replacement and in an unspecified order.
+    ///
+    /// This uses `reservoir::ReservoirSampler`, which consumes the
+    /// whole iterator but only generates random numbers for the
+    /// elements that are (provisionally) chosen.
     ///
     /// # Example
     ///
@@ -508,19 +984,36 @@ pub trait Rng {
     /// }
     /// ~~~
     fn sample<A, T: Iterator<A>>(&mut self, iter: T, n: uint) -> ~[A] {
-        let mut reservoir : ~[A] = vec::with_capacity(n);
-        for (i, elem) in iter.enumerate() {
-            if i < n {
-                reservoir.push(elem);
-                continue;
-            }
+        let mut sampler = ReservoirSampler::new(n);
+        sampler.feed_iter(self, iter);
+        sampler.unwrap()
+    }
 
-            let k = self.gen_integer_range(0, i + 1);
-            if k < reservoir.len() {
-                reservoir[k] = elem
-            }
-        }
-        reservoir
+    /// Randomly sample up to `n` elements from an iterator of `(item,
+    /// weight)` pairs, without replacement and in an unspecified
+    /// order. Each successive element of the sample is chosen from the
+    /// remaining items with probability proportional to its weight;
+    /// items with weight 0 are never chosen. Fails if any weight is
+    /// negative, infinite or NaN.
+    ///
+    /// This uses `reservoir::WeightedReservoirSampler`.
+    ///
+    /// # Example
+    ///
+    /// ~~~ {.rust}
+    /// use std::rand;
+    ///
+    /// fn main() {
+    ///    let rng = rand::task_rng();
+    ///    // larger numbers are more likely to be chosen.
+    ///    let sample = rng.sample_weighted(range(1, 100).map(|i| (i, i as f64)), 5);
+    ///    printfln!(sample);
+    /// }
+    /// ~~~
+    fn sample_weighted<A, T: Iterator<(A, f64)>>(&mut self, iter: T, n: uint) -> ~[A] {
+        let mut sampler = WeightedReservoirSampler::new(n);
+        sampler.feed_iter(self, iter);
+        sampler.unwrap()
     }
 }
 
@@ -631,6 +1124,99 @@ impl Rand for f64 {
     }
 }
 
+/// A wrapper for generating floating point numbers uniformly in the
+/// open interval `(0,1)` (not including either endpoint).
+///
+/// Use `Closed01` for the closed interval `[0,1]`, and the default
+/// `Rand` implementation for `f32` and `f64` for the half-open
+/// `[0,1)`.
+///
+/// # Example
+///
+/// ~~~ {.rust}
+/// use std::rand::{random, Open01};
+///
+/// fn main() {
+///     // safe to take the logarithm of, since it is never 0.
+///     let Open01(val) = random::<Open01<f32>>();
+///     println!("{}", val.ln());
+/// }
+/// ~~~
+pub struct Open01<F>(F);
+
+/// A wrapper for generating floating point numbers uniformly in the
+/// closed interval `[0,1]` (including both endpoints).
+///
+/// Use `Open01` for the open interval `(0,1)`, and the default `Rand`
+/// implementation for `f32` and `f64` for the half-open `[0,1)`.
+pub struct Closed01<F>(F);
+
+/// A wrapper for generating floating point numbers in `[0,1)` with as
+/// much precision as the type allows.
+///
+/// The default `Rand` implementations for `f32` and `f64` generate
+/// multiples of 2^-24 and 2^-53 respectively, so, for example, values
+/// smaller than 2^-53 are always rounded down to 0. This instead
+/// chooses the exponent with the appropriate geometric distribution,
+/// so that every representable value in `[0,1)` (above the subnormal
+/// range) can occur, with probability proportional to the gap to the
+/// next one. This consumes more than one random number when the
+/// result is less than 2^-32 (`f32`) or 2^-64 (`f64`).
+pub struct FullPrecision<F>(F);
+
+macro_rules! float_wrapper_impls {
+    ($ty:ty, $next:ident, $uty:ty, $bits:expr, $mantissa_bits:expr, $min_exponent:expr) => {
+        impl Rand for Open01<$ty> {
+            #[inline]
+            fn rand<R: Rng>(rng: &mut R) -> Open01<$ty> {
+                // the midpoints of the intervals of width
+                // 2^-$mantissa_bits, which are exactly representable.
+                let x = (rng.$next() >> ($bits - $mantissa_bits)) as $ty;
+                Open01((x + 0.5) / (1 as $uty << $mantissa_bits) as $ty)
+            }
+        }
+
+        impl Rand for Closed01<$ty> {
+            #[inline]
+            fn rand<R: Rng>(rng: &mut R) -> Closed01<$ty> {
+                // divide by the largest value that can be generated,
+                // so it becomes 1.
+                let x = rng.$next() >> ($bits - $mantissa_bits - 1);
+                Closed01(x as $ty / ((1 as $uty << ($mantissa_bits + 1)) - 1) as $ty)
+            }
+        }
+
+        impl Rand for FullPrecision<$ty> {
+            fn rand<R: Rng>(rng: &mut R) -> FullPrecision<$ty> {
+                let mantissa = rng.$next() >> ($bits - $mantissa_bits);
+
+                // the result is in [2^exponent, 2^(exponent + 1)) with
+                // probability 2^exponent, i.e. the exponent is
+                // decremented once for each trailing zero bit.
+                let mut exponent = -1;
+                loop {
+                    let bits = rng.$next();
+                    if bits != 0 {
+                        exponent -= bits.trailing_zeros() as int;
+                        break;
+                    }
+                    exponent -= $bits;
+                    if exponent < $min_exponent {
+                        // smaller than the smallest subnormal.
+                        return FullPrecision(0.0);
+                    }
+                }
+
+                let fraction = 1.0 + mantissa as $ty / (1 as $uty << $mantissa_bits) as $ty;
+                FullPrecision(fraction * (2.0 as $ty).pow(&(exponent as $ty)))
+            }
+        }
+    }
+}
+
+float_wrapper_impls! { f32, next_u32, u32, 32, 23, -149 }
+float_wrapper_impls! { f64, next_u64, u64, 64, 52, -1074 }
+
 impl Rand for bool {
     #[inline]
     fn rand<R: Rng>(rng: &mut R) -> bool {
@@ -700,6 +1286,8 @@ impl<T: 'static + Rand> Rand for @T {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::{vec, i64, u64, uint};
+    use std::hashmap::{HashMap, HashSet};
 
     #[test]
     fn test_choose_nonempty() {
@@ -708,6 +1296,62 @@ mod tests {
         assert_eq!(r.choose_nonempty(v), &1i);
     }
 
+    #[test]
+    fn test_gen_range() {
+        let mut r = rng();
+        for _ in range(0, 1000) {
+            let a = r.gen_range(-3i, 42);
+            assert!(a >= -3 && a < 42);
+            assert_eq!(r.gen_range(0, 1), 0);
+            assert_eq!(r.gen_range(-12, -11), -12);
+
+            let b = r.gen_range(i64::min_value, i64::max_value);
+            assert!(b < i64::max_value);
+            let c = r.gen_range(10u64, u64::max_value);
+            assert!(c >= 10);
+
+            let d = r.gen_range(-1e10f64, 1e10);
+            assert!(d >= -1e10 && d < 1e10);
+            let e = r.gen_range(0.5f32, 0.5000001);
+            assert!(e >= 0.5 && e < 0.5000001);
+        }
+    }
+
+    #[test]
+    fn test_gen_range_inclusive() {
+        let mut r = rng();
+        r.gen_range_inclusive(0u64, u64::max_value);
+        r.gen_range_inclusive(i64::min_value, i64::max_value);
+        let mut seen = [false, .. 3];
+        for _ in range(0, 1000) {
+            seen[r.gen_range_inclusive(0u, 2)] = true;
+        }
+        assert_eq!(seen.to_owned(), ~[true, true, true]);
+        assert_eq!(r.gen_range_inclusive(7u8, 7), 7);
+        assert_eq!(r.gen_range_inclusive(-2.5f64, -2.5), -2.5);
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_gen_range_fail_int() {
+        let mut r = rng();
+        r.gen_range(5i, -2);
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_gen_range_fail_float() {
+        let mut r = rng();
+        r.gen_range(1.0, 1.0);
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_gen_range_inclusive_fail() {
+        let mut r = rng();
+        r.gen_range_inclusive(5u, 4);
+    }
+
     #[test]
     fn test_choose() {
         let mut r = rng();
@@ -718,6 +1362,175 @@ mod tests {
         assert_eq!(*r.choose(v).unwrap(), 1i);
     }
 
+    #[test]
+    fn test_choose_mut() {
+        let mut r = rng();
+        let mut v: [int, .. 0] = [];
+        assert!(r.choose_mut(v).is_none());
+        let mut v = [1i, 1, 1];
+        *r.choose_mut(v).unwrap() = 2;
+        assert_eq!(v.iter().fold(0, |a, &b| a + b), 5);
+    }
+
+    #[test]
+    fn test_choose_weighted() {
+        let mut r = rng();
+        let empty: [(int, uint), .. 0] = [];
+        assert!(r.choose_weighted(empty).is_none());
+        assert!(r.choose_weighted([(1i, 0u), (2, 0)]).is_none());
+        assert_eq!(r.choose_weighted([(1i, 0u), (2, 3), (3, 0)]), Some(&2));
+
+        let mut counts = [0u, .. 3];
+        for _ in range(0, 7000) {
+            counts[*r.choose_weighted([(0u, 4u), (1, 2), (2, 1)]).unwrap()] += 1;
+        }
+        // the standard deviations of the counts are all below 42.
+        assert!(counts[0] > 3750 && counts[0] < 4250);
+        assert!(counts[1] > 1750 && counts[1] < 2250);
+        assert!(counts[2] > 750 && counts[2] < 1250);
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_choose_weighted_overflow() {
+        let mut r = rng();
+        r.choose_weighted([(0i, uint::max_value), (1, 2)]);
+    }
+
+    #[test]
+    fn test_choose_multiple() {
+        let mut r = rng();
+        let v = [0u, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        for k in range(0u, 12) {
+            let chosen = r.choose_multiple(v, k);
+            assert_eq!(chosen.len(), if k < 10 { k } else { 10 });
+            let mut seen = [false, .. 10];
+            for &&x in chosen.iter() {
+                assert!(!seen[x]);
+                seen[x] = true;
+            }
+        }
+    }
+
+    #[test]
+    fn test_gen_weighted_bool() {
+        let mut r = rng();
+        assert!(r.gen_weighted_bool(0));
+        assert!(r.gen_weighted_bool(1));
+        let count = range(0, 1000).count(|_| r.gen_weighted_bool(4));
+        assert!(count > 180 && count < 320);
+    }
+
+    #[test]
+    fn test_gen_bool() {
+        let mut r = rng();
+        for _ in range(0, 1000) {
+            assert!(!r.gen_bool(0.0));
+            assert!(r.gen_bool(1.0));
+        }
+        let count = range(0, 1000).count(|_| r.gen_bool(0.25));
+        assert!(count > 180 && count < 320);
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_gen_bool_invalid() {
+        let mut r = rng();
+        r.gen_bool(1.5);
+    }
+
+    #[test]
+    fn test_sample_indices() {
+        let mut r = rng();
+        // each of these uses one of the three methods.
+        for &(n, k) in [(0u, 0u), (10, 10), (100, 30), (1000, 20),
+                        (1_000_000_000, 32), (1000, 100), (1_000_000_000, 1000)].iter() {
+            let indices = r.sample_indices(n, k);
+            assert_eq!(indices.len(), k);
+            let mut seen = HashSet::new();
+            for &i in indices.iter() {
+                assert!(i < n);
+                assert!(seen.insert(i));
+            }
+        }
+    }
+
+    #[test]
+    fn test_sample_indices_uniform() {
+        // every index should be chosen with probability k / n, whichever
+        // method is used.
+        let mut r = rng();
+        for &(n, k) in [(8u, 4u), (200, 3), (200, 40)].iter() {
+            let mut counts = vec::from_elem(n, 0u);
+            let trials = 20000 / k;
+            for _ in range(0, trials) {
+                for &i in r.sample_indices(n, k).iter() {
+                    counts[i] += 1;
+                }
+            }
+            let p = k as f64 / n as f64;
+            let expected = trials as f64 * p;
+            let sd = (trials as f64 * p * (1.0 - p)).sqrt();
+            for &count in counts.iter() {
+                assert!((count as f64 - expected).abs() <= 6.0 * sd,
+                        "n = {}, k = {}: got {}, expected {}", n, k, count, expected);
+            }
+        }
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_sample_indices_invalid() {
+        let mut r = rng();
+        r.sample_indices(3, 4);
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut r = rng();
+        let v = r.sample(range(0, 20), 5);
+        assert_eq!(v.len(), 5);
+        let distinct: HashSet<int> = v.iter().map(|&x| x).collect();
+        assert_eq!(distinct.len(), 5);
+        assert_eq!(r.sample(range(0, 3), 5).len(), 3);
+
+        let v = r.sample_weighted(range(0, 20).map(|i| (i, (i % 2) as f64)), 5);
+        assert_eq!(v.len(), 5);
+        assert!(v.iter().all(|&i| i % 2 == 1));
+    }
+
+    #[test]
+    fn test_partial_shuffle() {
+        let mut r = rng();
+        let mut v = [0u, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        r.partial_shuffle(v, 0);
+        assert_eq!(v.to_owned(), ~[0u, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        for k in range(0u, 11) {
+            r.partial_shuffle(v, k);
+            let mut seen = [false, .. 10];
+            for &x in v.iter() {
+                assert!(!seen[x]);
+                seen[x] = true;
+            }
+        }
+
+        // the first element should be uniformly distributed.
+        let mut counts = [0u, .. 4];
+        for _ in range(0, 4000) {
+            let mut v = [0u, 1, 2, 3];
+            r.partial_shuffle(v, 1);
+            counts[v[0]] += 1;
+        }
+        assert!(counts.iter().all(|&c| c > 850 && c < 1150));
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_partial_shuffle_invalid() {
+        let mut r = rng();
+        r.partial_shuffle([1, 2], 3);
+    }
+
     #[test]
     fn test_shuffle() {
         let mut r = rng();
@@ -726,6 +1539,198 @@ mod tests {
         assert_eq!(r.shuffle(~[1, 1, 1]), ~[1, 1, 1]);
     }
 
+    #[test]
+    fn test_shuffle_cyclic() {
+        let mut r = rng();
+        let mut empty: ~[int] = ~[];
+        r.shuffle_cyclic(empty);
+        let mut one = [1];
+        r.shuffle_cyclic(one);
+        assert_eq!(one[0], 1);
+
+        for n in range(2u, 10) {
+            let mut next: ~[uint] = range(0, n).collect();
+            r.shuffle_cyclic(next);
+            // following the links from 0 visits everything before
+            // getting back to 0.
+            let mut i = next[0];
+            let mut steps = 1;
+            while i != 0 {
+                i = next[i];
+                steps += 1;
+                assert!(steps <= n);
+            }
+            assert_eq!(steps, n);
+        }
+
+        // each of the 2 cycles of length 3 should be equally likely.
+        let mut count = 0;
+        for _ in range(0, 1000) {
+            let mut v = [0u, 1, 2];
+            r.shuffle_cyclic(v);
+            if v.to_owned() == ~[1u, 2, 0] { count += 1 }
+        }
+        assert!(count > 400 && count < 600);
+    }
+
+    #[test]
+    fn test_random_derangement() {
+        let mut r = rng();
+        assert_eq!(r.random_derangement(0), ~[]);
+        assert_eq!(r.random_derangement(2), ~[1u, 0]);
+        for n in range(2u, 20) {
+            let perm = r.random_derangement(n);
+            let mut seen = vec::from_elem(n, false);
+            for (i, &x) in perm.iter().enumerate() {
+                assert!(i != x);
+                assert!(!seen[x]);
+                seen[x] = true;
+            }
+        }
+
+        // there are 9 derangements of 4 elements, and each should be
+        // equally likely.
+        let mut counts = HashMap::new();
+        for _ in range(0, 9000) {
+            let perm = r.random_derangement(4);
+            counts.insert_or_update_with(perm, 1u, |_, c| *c += 1);
+        }
+        assert_eq!(counts.len(), 9);
+        for (_, &c) in counts.iter() {
+            assert!(c > 800 && c < 1200, "count {}", c);
+        }
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_random_derangement_invalid() {
+        rng().random_derangement(1);
+    }
+
+    struct ConstRng { i: u64 }
+    impl Rng for ConstRng {
+        fn next_u64(&mut self) -> u64 { self.i }
+        fn entropy_u64(&self) -> uint { 8 }
+    }
+
+    #[test]
+    fn test_fill_bytes_default() {
+        let mut r = ConstRng { i: 0x0807060504030201 };
+        let mut v = [0u8, .. 11];
+        r.fill_bytes(v);
+        assert_eq!(v.to_owned(), ~[1u8, 2, 3, 4, 5, 6, 7, 8, 1, 2, 3]);
+        assert_eq!(r.entropy_bytes(11), 16);
+    }
+
+    #[test]
+    fn test_float_ranges() {
+        // the extreme outputs of the underlying RNG.
+        let mut zero = ConstRng { i: 0 };
+        let mut max = ConstRng { i: u64::max_value };
+
+        assert_eq!(zero.gen::<f32>(), 0.0);
+        assert_eq!(zero.gen::<f64>(), 0.0);
+        assert!(max.gen::<f32>() < 1.0);
+        assert!(max.gen::<f64>() < 1.0);
+
+        let Open01(a) = zero.gen::<Open01<f32>>();
+        let Open01(b) = max.gen::<Open01<f32>>();
+        assert!(0.0 < a && b < 1.0);
+        let Open01(a) = zero.gen::<Open01<f64>>();
+        let Open01(b) = max.gen::<Open01<f64>>();
+        assert!(0.0 < a && b < 1.0);
+
+        let Closed01(a) = zero.gen::<Closed01<f32>>();
+        let Closed01(b) = max.gen::<Closed01<f32>>();
+        assert_eq!((a, b), (0.0, 1.0));
+        let Closed01(a) = zero.gen::<Closed01<f64>>();
+        let Closed01(b) = max.gen::<Closed01<f64>>();
+        assert_eq!((a, b), (0.0, 1.0));
+
+        let FullPrecision(a) = zero.gen::<FullPrecision<f64>>();
+        let FullPrecision(b) = max.gen::<FullPrecision<f64>>();
+        assert_eq!(a, 0.0);
+        assert!(b < 1.0);
+        // 1 << 63 has 63 trailing zeros, so the exponent is -64.
+        let FullPrecision(c) = (ConstRng { i: 1 << 63 }).gen::<FullPrecision<f64>>();
+        assert!(c >= 5.421010862427522e-20 && c < 1.0842021724855044e-19);
+    }
+
+    #[test]
+    fn test_float_wrappers() {
+        let mut r = rng();
+        for _ in range(0, 1000) {
+            let Open01(a) = r.gen::<Open01<f32>>();
+            let Open01(b) = r.gen::<Open01<f64>>();
+            assert!(0.0 < a && a < 1.0 && 0.0 < b && b < 1.0);
+            let Closed01(a) = r.gen::<Closed01<f32>>();
+            let Closed01(b) = r.gen::<Closed01<f64>>();
+            assert!(0.0 <= a && a <= 1.0 && 0.0 <= b && b <= 1.0);
+            let FullPrecision(a) = r.gen::<FullPrecision<f32>>();
+            let FullPrecision(b) = r.gen::<FullPrecision<f64>>();
+            assert!(0.0 <= a && a < 1.0 && 0.0 <= b && b < 1.0);
+        }
+    }
+
+    #[test]
+    fn test_try_defaults() {
+        let mut r = ConstRng { i: 0x0807060504030201 };
+        assert_eq!(r.try_next_u64(), Ok(0x0807060504030201));
+        assert_eq!(r.try_next_u32(), Ok(0x04030201));
+        let mut v = [0u8, .. 3];
+        assert_eq!(r.try_fill_bytes(v), Ok(()));
+        assert_eq!(v.to_owned(), ~[1u8, 2, 3]);
+    }
+
+    #[test]
+    fn test_error_to_str() {
+        let e = Error::new(NotReady, ~"out of entropy");
+        assert_eq!(e.to_str(), ~"RNG not ready: out of entropy");
+    }
+
+    #[test]
+    fn test_os_rng_try() {
+        let mut r = rng::OSRng::try_new().unwrap();
+        assert!(r.try_next_u32().is_ok());
+        assert!(r.try_next_u64().is_ok());
+        let mut v = [0u8, .. 1000];
+        assert!(r.try_fill_bytes(v).is_ok());
+    }
+
+    /// Check that `a.fill_bytes` gives the same bytes as the
+    /// little-endian results of `b.next_u32`, given identically
+    /// seeded RNGs.
+    fn check_fill_bytes_u32<R: Rng>(mut a: R, mut b: R) {
+        // long enough to cross a refill of the internal buffers.
+        let mut v = vec::from_elem(4 * 1000, 0u8);
+        a.fill_bytes(v);
+        for chunk in v.chunk_iter(4) {
+            let x = b.next_u32();
+            assert_eq!(chunk.to_owned(),
+                       ~[x as u8, (x >> 8) as u8, (x >> 16) as u8, (x >> 24) as u8]);
+        }
+    }
+
+    #[test]
+    fn test_fill_bytes_overrides() {
+        let seed = [1u32, 2, 3, 4];
+        check_fill_bytes_u32::<rng::Isaac>(SeedableRng::from_seed(seed.as_slice()),
+                                           SeedableRng::from_seed(seed.as_slice()));
+        check_fill_bytes_u32::<rng::MT19937>(SeedableRng::from_seed(seed.as_slice()),
+                                             SeedableRng::from_seed(seed.as_slice()));
+
+        let seed = [1u64, 2, 3, 4];
+        let mut a: rng::Isaac64 = SeedableRng::from_seed(seed.as_slice());
+        let mut b: rng::Isaac64 = SeedableRng::from_seed(seed.as_slice());
+        let mut v = vec::from_elem(8 * 300, 0u8);
+        a.fill_bytes(v);
+        for chunk in v.chunk_iter(8) {
+            let x = b.next_u64();
+            let expected = vec::from_fn(8, |i| (x >> (8 * i)) as u8);
+            assert_eq!(chunk.to_owned(), expected);
+        }
+    }
+
     #[test]
     fn test_iter() {
         let mut rng = rng().rand_iter();
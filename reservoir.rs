@@ -0,0 +1,377 @@
+//! Streaming random sampling of a fixed number of items from a
+//! sequence of unknown length.
+
+use std::{uint, vec};
+use {Rng, Open01};
+
+/// The number of items to skip before the next one is accepted, given
+/// a uniform `u` in (0, 1) and `ln_q = ln(1 - p)` where `p` is the
+/// probability of accepting any single item.
+fn geometric_skip(u: f64, ln_q: f64) -> uint {
+    let skip = (u.ln() / ln_q).floor();
+    // `ln_q` is 0 (or so close that this overflows) when an item is
+    // vanishingly unlikely to be accepted.
+    if skip >= uint::max_value as f64 || !skip.is_finite() {
+        uint::max_value
+    } else {
+        skip as uint
+    }
+}
+
+/// A sampler that chooses `k` items uniformly at random (without
+/// replacement) from a stream of items that are fed in one at a time,
+/// so that every subset of `k` of the items seen so far is equally
+/// likely to be the sample.
+///
+/// This uses Algorithm L of K.-H. Li, [*Reservoir-Sampling Algorithms
+/// of Time Complexity O(n(1 + log(N/n)))*](http://dl.acm.org/citation.cfm?id=198435)
+/// (1994), which computes how many items to skip before the next one
+/// is accepted, so only `O(k (1 + log(n / k)))` random numbers are
+/// used for a stream of `n` items.
+///
+/// # Example
+///
+/// ~~~ {.rust}
+/// use std::rand;
+/// use std::rand::reservoir::ReservoirSampler;
+///
+/// fn main() {
+///     let mut rng = rand::task_rng();
+///     let mut sampler = ReservoirSampler::new(3);
+///     for line in ["a", "b", "c", "d", "e"].iter() {
+///         sampler.feed(rng, *line);
+///     }
+///     printfln!(sampler.sample());
+/// }
+/// ~~~
+pub struct ReservoirSampler<T> {
+    priv k: uint,
+    priv reservoir: ~[T],
+    priv seen: uint,
+    // the largest of the (implicit) random keys of the items in the
+    // reservoir, when the keys are uniform on (0, 1).
+    priv w: f64,
+    // the index of the next item that will be accepted.
+    priv next: uint
+}
+
+impl<T> ReservoirSampler<T> {
+    /// Create a sampler that chooses `k` items.
+    pub fn new(k: uint) -> ReservoirSampler<T> {
+        ReservoirSampler {
+            k: k,
+            reservoir: vec::with_capacity(k),
+            seen: 0,
+            w: 1.0,
+            next: k
+        }
+    }
+
+    /// Offer `item` to the sampler, which either stores it in the
+    /// sample or drops it. This only uses `rng` if the item is
+    /// accepted.
+    pub fn feed<R: Rng>(&mut self, rng: &mut R, item: T) {
+        let index = self.seen;
+        self.seen += 1;
+        if self.k == 0 {
+            return;
+        }
+
+        if index < self.k {
+            self.reservoir.push(item);
+            if self.seen == self.k {
+                self.advance(rng);
+            }
+        } else if index == self.next {
+            let i = rng.gen_range(0, self.k);
+            self.reservoir[i] = item;
+            self.advance(rng);
+        }
+    }
+
+    /// Offer every item of `iter` to the sampler.
+    pub fn feed_iter<R: Rng, I: Iterator<T>>(&mut self, rng: &mut R, mut iter: I) {
+        for item in iter {
+            self.feed(rng, item);
+        }
+    }
+
+    /// Compute the new `w` and the index of the next item to accept,
+    /// after an item was accepted into a full reservoir.
+    fn advance<R: Rng>(&mut self, rng: &mut R) {
+        let Open01(u) = rng.gen::<Open01<f64>>();
+        self.w *= (u.ln() / self.k as f64).exp();
+
+        let Open01(u) = rng.gen::<Open01<f64>>();
+        let skip = geometric_skip(u, (-self.w).ln_1p());
+        self.next = if skip >= uint::max_value - self.seen {
+            uint::max_value
+        } else {
+            self.seen + skip
+        };
+    }
+
+    /// The number of items that have been fed to the sampler.
+    pub fn seen(&self) -> uint {
+        self.seen
+    }
+
+    /// The current sample: `k` items, or every item if fewer than `k`
+    /// have been seen. The order of the items is unspecified.
+    pub fn sample<'a>(&'a self) -> &'a [T] {
+        self.reservoir.as_slice()
+    }
+
+    /// Consume the sampler, returning the sample.
+    pub fn unwrap(self) -> ~[T] {
+        self.reservoir
+    }
+}
+
+/// A sampler that chooses `k` items at random (without replacement)
+/// from a stream of weighted items, so that each item is included in
+/// the sample with probability increasing with its weight.
+/// Specifically, each successive item in the sample is chosen from
+/// those remaining with probability proportional to its weight.
+///
+/// This uses the A-ExpJ algorithm of P. S. Efraimidis and P. G.
+/// Spirakis, [*Weighted random sampling with a
+/// reservoir*](http://dx.doi.org/10.1016/j.ipl.2005.11.003) (2006),
+/// which gives each item the random key `u^(1/weight)` and keeps the
+/// `k` largest, skipping directly to the next item that will be
+/// accepted.
+pub struct WeightedReservoirSampler<T> {
+    priv k: uint,
+    // a binary min-heap of `(ln(key), item)`, so the first element is
+    // the next to be replaced.
+    priv heap: ~[(f64, T)],
+    priv seen: uint,
+    // the total weight of the items to skip before the next one is
+    // accepted.
+    priv skip_weight: f64
+}
+
+impl<T> WeightedReservoirSampler<T> {
+    /// Create a sampler that chooses `k` items.
+    pub fn new(k: uint) -> WeightedReservoirSampler<T> {
+        WeightedReservoirSampler {
+            k: k,
+            heap: vec::with_capacity(k),
+            seen: 0,
+            skip_weight: 0.0
+        }
+    }
+
+    /// Offer `item` with weight `weight` to the sampler, which either
+    /// stores it in the sample or drops it. Items with weight 0 are
+    /// never chosen. Fails if `weight` is negative, infinite or NaN.
+    pub fn feed<R: Rng>(&mut self, rng: &mut R, item: T, weight: f64) {
+        assert!(weight >= 0.0 && weight.is_finite(),
+                "WeightedReservoirSampler.feed called with invalid weight {}", weight);
+        self.seen += 1;
+        if weight == 0.0 || self.k == 0 {
+            return;
+        }
+
+        if self.heap.len() < self.k {
+            let Open01(u) = rng.gen::<Open01<f64>>();
+            self.push(u.ln() / weight, item);
+            if self.heap.len() == self.k {
+                self.advance(rng);
+            }
+            return;
+        }
+
+        self.skip_weight -= weight;
+        if self.skip_weight <= 0.0 {
+            // the new key must beat the current smallest one, i.e. be
+            // `r^(1/weight)` for `r` uniform in (t, 1).
+            let t = (self.min_key() * weight).exp();
+            let Open01(u) = rng.gen::<Open01<f64>>();
+            let r = t + (1.0 - t) * u;
+            self.replace_min(r.ln() / weight, item);
+            self.advance(rng);
+        }
+    }
+
+    /// Offer every `(item, weight)` pair of `iter` to the sampler.
+    pub fn feed_iter<R: Rng, I: Iterator<(T, f64)>>(&mut self, rng: &mut R, mut iter: I) {
+        for (item, weight) in iter {
+            self.feed(rng, item, weight);
+        }
+    }
+
+    /// Choose the weight to skip before the next item is accepted,
+    /// after the smallest key changed.
+    fn advance<R: Rng>(&mut self, rng: &mut R) {
+        let Open01(u) = rng.gen::<Open01<f64>>();
+        self.skip_weight = u.ln() / self.min_key();
+    }
+
+    /// The logarithm of the smallest key in the sample.
+    fn min_key(&self) -> f64 {
+        match self.heap[0] { (key, _) => key }
+    }
+
+    fn push(&mut self, key: f64, item: T) {
+        self.heap.push((key, item));
+        let mut i = self.heap.len() - 1;
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if self.key(parent) <= self.key(i) {
+                break;
+            }
+            self.heap.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    fn replace_min(&mut self, key: f64, item: T) {
+        self.heap[0] = (key, item);
+        let len = self.heap.len();
+        let mut i = 0;
+        loop {
+            let mut smallest = i;
+            for &child in [2 * i + 1, 2 * i + 2].iter() {
+                if child < len && self.key(child) < self.key(smallest) {
+                    smallest = child;
+                }
+            }
+            if smallest == i {
+                break;
+            }
+            self.heap.swap(i, smallest);
+            i = smallest;
+        }
+    }
+
+    fn key(&self, i: uint) -> f64 {
+        match self.heap[i] { (key, _) => key }
+    }
+
+    /// The number of items that have been fed to the sampler.
+    pub fn seen(&self) -> uint {
+        self.seen
+    }
+
+    /// The current sample: `k` items, or every item with a positive
+    /// weight if fewer than `k` have been seen. The order of the items
+    /// is unspecified.
+    pub fn sample<'a>(&'a self) -> ~[&'a T] {
+        self.heap.iter().map(|&(_, ref item)| item).collect()
+    }
+
+    /// Consume the sampler, returning the sample.
+    pub fn unwrap(self) -> ~[T] {
+        self.heap.move_iter().map(|(_, item)| item).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec;
+
+    #[test]
+    fn test_reservoir_small() {
+        let mut rng = ::rng();
+        let mut sampler = ReservoirSampler::new(5);
+        sampler.feed_iter(&mut rng, range(0, 3));
+        assert_eq!(sampler.seen(), 3);
+        assert_eq!(sampler.sample().to_owned(), ~[0, 1, 2]);
+
+        let mut sampler = ReservoirSampler::new(0);
+        sampler.feed_iter(&mut rng, range(0, 100));
+        assert_eq!(sampler.seen(), 100);
+        assert!(sampler.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_reservoir_uniform() {
+        // every item should be chosen with probability k / n.
+        let mut rng = ::rng();
+        let (n, k, trials) = (50u, 5u, 10000u);
+        let mut counts = vec::from_elem(n, 0u);
+        for _ in range(0, trials) {
+            let mut sampler = ReservoirSampler::new(k);
+            sampler.feed_iter(&mut rng, range(0, n));
+            let sample = sampler.unwrap();
+            assert_eq!(sample.len(), k);
+            for &i in sample.iter() {
+                counts[i] += 1;
+            }
+        }
+        let p = k as f64 / n as f64;
+        let expected = trials as f64 * p;
+        let sd = (trials as f64 * p * (1.0 - p)).sqrt();
+        for &count in counts.iter() {
+            assert!((count as f64 - expected).abs() <= 6.0 * sd,
+                    "got {}, expected {}", count, expected);
+        }
+    }
+
+    #[test]
+    fn test_reservoir_long_stream() {
+        let mut rng = ::rng();
+        let mut sampler = ReservoirSampler::new(10);
+        sampler.feed_iter(&mut rng, range(0u, 1000000));
+        let sample = sampler.unwrap();
+        assert_eq!(sample.len(), 10);
+        // the sample is very unlikely to be concentrated at the start.
+        assert!(sample.iter().any(|&x| x >= 500000));
+    }
+
+    #[test]
+    fn test_weighted_reservoir() {
+        let mut rng = ::rng();
+        // with k = 1 each item is chosen with probability proportional
+        // to its weight.
+        let weights = [1.0, 0.0, 2.0, 3.0, 4.0];
+        let trials = 10000u;
+        let mut counts = [0u, .. 5];
+        for _ in range(0, trials) {
+            let mut sampler = WeightedReservoirSampler::new(1);
+            for (i, &w) in weights.iter().enumerate() {
+                sampler.feed(&mut rng, i, w);
+            }
+            counts[sampler.unwrap()[0]] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        for (&count, &w) in counts.iter().zip(weights.iter()) {
+            let p = w / 10.0;
+            let expected = trials as f64 * p;
+            let sd = (trials as f64 * p * (1.0 - p)).sqrt();
+            assert!((count as f64 - expected).abs() <= 6.0 * sd,
+                    "weight {}: got {}, expected {}", w, count, expected);
+        }
+    }
+
+    #[test]
+    fn test_weighted_reservoir_distinct() {
+        let mut rng = ::rng();
+        let mut sampler = WeightedReservoirSampler::new(10);
+        sampler.feed_iter(&mut rng, range(0u, 10000).map(|i| (i, (i % 7) as f64)));
+        let sample = sampler.unwrap();
+        assert_eq!(sample.len(), 10);
+        let mut seen = vec::from_elem(10000, false);
+        for &i in sample.iter() {
+            assert!(i % 7 != 0);
+            assert!(!seen[i]);
+            seen[i] = true;
+        }
+
+        let mut sampler = WeightedReservoirSampler::new(3);
+        sampler.feed(&mut rng, 'a', 1.0);
+        sampler.feed(&mut rng, 'b', 0.0);
+        assert_eq!(sampler.seen(), 2);
+        assert_eq!(sampler.sample(), ~[&'a']);
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_weighted_reservoir_invalid() {
+        let mut rng = ::rng();
+        let mut sampler = WeightedReservoirSampler::new(3);
+        sampler.feed(&mut rng, 1, -1.0);
+    }
+}
@@ -0,0 +1,313 @@
+use Rng;
+use SeedableRng;
+use rng::{seed, expand_seed};
+
+static KEY_WORDS: uint = 8;
+static STATE_WORDS: uint = 16;
+static CHACHA_ROUNDS: uint = 20;
+
+/// A random number generator that uses the ChaCha stream cipher of
+/// D. J. Bernstein, [*ChaCha, a variant of
+/// Salsa20*](http://cr.yp.to/chacha/chacha-20080128.pdf) (2008), with
+/// 20 rounds by default, or the faster but less conservative 8 or 12.
+///
+/// ChaCha20 is a well-analysed cipher, standardised in [RFC
+/// 7539](https://tools.ietf.org/html/rfc7539), so this generator is
+/// suitable for cryptographic purposes, assuming it is seeded from a
+/// secure source such as `OSRng` (as `new` does).
+///
+/// The seed is a 256-bit key. This uses the original layout, with a
+/// 64-bit block counter and a 64-bit stream (nonce): each of the 2^64
+/// streams of a key is an independent sequence of 2^68 words, which
+/// can be selected with `set_stream` and seeked with `set_word_pos`.
+pub struct ChaChaRng {
+    // the output of the most recent block.
+    priv buffer: [u32, .. STATE_WORDS],
+    // the constants, key, counter of the next block, and stream.
+    priv state: [u32, .. STATE_WORDS],
+    // the next word of `buffer` to return.
+    priv index: uint,
+    priv rounds: uint
+}
+
+macro_rules! quarter_round{
+    ($x:ident, $a:expr, $b:expr, $c:expr, $d:expr) => {{
+        $x[$a] += $x[$b]; $x[$d] ^= $x[$a]; $x[$d] = rotl($x[$d], 16);
+        $x[$c] += $x[$d]; $x[$b] ^= $x[$c]; $x[$b] = rotl($x[$b], 12);
+        $x[$a] += $x[$b]; $x[$d] ^= $x[$a]; $x[$d] = rotl($x[$d], 8);
+        $x[$c] += $x[$d]; $x[$b] ^= $x[$c]; $x[$b] = rotl($x[$b], 7);
+    }}
+}
+
+#[inline]
+fn rotl(x: u32, k: u32) -> u32 {
+    (x << k) | (x >> (32 - k))
+}
+
+/// Compute the ChaCha block for `input` with `rounds` rounds into
+/// `output`.
+fn chacha_block(output: &mut [u32, .. STATE_WORDS], input: &[u32, .. STATE_WORDS],
+                rounds: uint) {
+    *output = *input;
+    for _ in range(0, rounds / 2) {
+        // columns
+        quarter_round!(output, 0, 4,  8, 12);
+        quarter_round!(output, 1, 5,  9, 13);
+        quarter_round!(output, 2, 6, 10, 14);
+        quarter_round!(output, 3, 7, 11, 15);
+        // diagonals
+        quarter_round!(output, 0, 5, 10, 15);
+        quarter_round!(output, 1, 6, 11, 12);
+        quarter_round!(output, 2, 7,  8, 13);
+        quarter_round!(output, 3, 4,  9, 14);
+    }
+    for i in range(0, STATE_WORDS) {
+        output[i] += input[i];
+    }
+}
+
+impl ChaChaRng {
+    /// Create a ChaCha20 random number generator with a random key
+    /// from the operating system.
+    pub fn new() -> ChaChaRng {
+        ChaChaRng::new_with_rounds(CHACHA_ROUNDS)
+    }
+
+    /// Create a ChaCha random number generator with `rounds` rounds
+    /// (which must be 8, 12 or 20) and a random key from the
+    /// operating system.
+    pub fn new_with_rounds(rounds: uint) -> ChaChaRng {
+        let s = unsafe { seed::<u32>(KEY_WORDS) };
+        ChaChaRng::from_seed_with_rounds(key_from_slice(s), rounds)
+    }
+
+    /// Create a ChaCha random number generator with `rounds` rounds
+    /// (which must be 8, 12 or 20) and the key `seed`, starting at the
+    /// beginning of stream 0.
+    pub fn from_seed_with_rounds(seed: [u32, .. KEY_WORDS], rounds: uint) -> ChaChaRng {
+        assert!(rounds == 8 || rounds == 12 || rounds == 20,
+                "ChaChaRng requires 8, 12 or 20 rounds (received {})", rounds);
+        let mut rng = ChaChaRng {
+            buffer: [0, .. STATE_WORDS],
+            state: [0, .. STATE_WORDS],
+            index: STATE_WORDS,
+            rounds: rounds
+        };
+        rng.reseed(seed);
+        rng
+    }
+
+    /// Create a ChaCha20 random number generator from a single `u64`,
+    /// expanded into a key with `SplitMix64`. This is only suitable
+    /// for reproducible simulations, since the key is easily guessed.
+    pub fn seed_from_u64(seed: u64) -> ChaChaRng {
+        SeedableRng::from_seed(key_from_slice(expand_seed::<u32>(seed, KEY_WORDS)))
+    }
+
+    /// Switch to the stream `stream`, keeping the same position within
+    /// it.
+    pub fn set_stream(&mut self, stream: u64) {
+        let pos = self.word_pos();
+        self.state[14] = stream as u32;
+        self.state[15] = (stream >> 32) as u32;
+        self.set_word_pos(pos);
+    }
+
+    /// The index of the next `u32` that will be generated within the
+    /// current stream, modulo 2^64.
+    pub fn word_pos(&self) -> u64 {
+        let counter = self.state[12] as u64 | self.state[13] as u64 << 32;
+        // `counter` is the block after the one in the buffer.
+        counter * (STATE_WORDS as u64) + (self.index as u64) - (STATE_WORDS as u64)
+    }
+
+    /// Seek to the `pos`th `u32` of the current stream, so that the
+    /// next call to `next_u32` returns it.
+    pub fn set_word_pos(&mut self, pos: u64) {
+        let block = pos / (STATE_WORDS as u64);
+        self.state[12] = block as u32;
+        self.state[13] = (block >> 32) as u32;
+        self.refill();
+        self.index = (pos % (STATE_WORDS as u64)) as uint;
+    }
+
+    /// Generate the next block into the buffer.
+    fn refill(&mut self) {
+        chacha_block(&mut self.buffer, &self.state, self.rounds);
+        self.index = 0;
+
+        // increment the 64-bit counter.
+        self.state[12] += 1;
+        if self.state[12] == 0 {
+            self.state[13] += 1;
+        }
+    }
+}
+
+/// Copy the start of `s` into a key.
+fn key_from_slice(s: &[u32]) -> [u32, .. KEY_WORDS] {
+    let mut key = [0, .. KEY_WORDS];
+    for (k, &x) in key.mut_iter().zip(s.iter()) {
+        *k = x;
+    }
+    key
+}
+
+impl Rng for ChaChaRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        if self.index == STATE_WORDS {
+            self.refill();
+        }
+        let x = self.buffer[self.index];
+        self.index += 1;
+        x
+    }
+
+    #[inline]
+    fn entropy_u32(&self) -> uint { 4 }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        // in the same order as `fill_bytes`.
+        let lo = self.next_u32() as u64;
+        let hi = self.next_u32() as u64;
+        hi << 32 | lo
+    }
+
+    #[inline]
+    fn entropy_u64(&self) -> uint { 8 }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        // the words are written in order, so this gives the ChaCha
+        // keystream.
+        let mut i = 0;
+        while i < dest.len() {
+            let word = self.next_u32();
+            i += ::write_le_bytes(dest.mut_slice_from(i), word as u64, 4);
+        }
+    }
+
+    #[inline]
+    fn entropy_bytes(&self, len: uint) -> uint { (len + 3) / 4 * 4 }
+}
+
+impl SeedableRng<[u32, .. KEY_WORDS]> for ChaChaRng {
+    /// Reseed with the key `seed`, returning to the beginning of
+    /// stream 0. This keeps the number of rounds.
+    fn reseed(&mut self, seed: [u32, .. KEY_WORDS]) {
+        // "expand 32-byte k"
+        self.state[0] = 0x61707865;
+        self.state[1] = 0x3320646e;
+        self.state[2] = 0x79622d32;
+        self.state[3] = 0x6b206574;
+        for i in range(0, KEY_WORDS) {
+            self.state[4 + i] = seed[i];
+        }
+        for i in range(12, STATE_WORDS) {
+            self.state[i] = 0;
+        }
+        self.index = STATE_WORDS;
+    }
+
+    /// Create a ChaCha20 random number generator with the key `seed`.
+    /// A generator constructed with a given seed will generate the
+    /// same sequence of values as all other generators constructed
+    /// with the same seed.
+    fn from_seed(seed: [u32, .. KEY_WORDS]) -> ChaChaRng {
+        ChaChaRng::from_seed_with_rounds(seed, CHACHA_ROUNDS)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use Rng;
+    use SeedableRng;
+    use std::vec;
+
+    #[test]
+    fn test_rfc7539_a1() {
+        // RFC 7539, appendix A.1, test vectors #1 and #2: the zero key,
+        // nonce and counter, then the next block.
+        let mut rng: ChaChaRng = SeedableRng::from_seed([0, .. 8]);
+        let v = vec::from_fn(32, |_| rng.next_u32());
+        assert_eq!(v, ~[0xade0b876, 0x903df1a0, 0xe56a5d40, 0x28bd8653,
+                        0xb819d2bd, 0x1aed8da0, 0xccef36a8, 0xc70d778b,
+                        0x7c5941da, 0x8d485751, 0x3fe02477, 0x374ad8b8,
+                        0xf4b8436a, 0x1ca11815, 0x69b687c3, 0x8665eeb2,
+
+                        0xbee7079f, 0x7a385155, 0x7c97ba98, 0x0d082d73,
+                        0xa0290fcb, 0x6965e348, 0x3e53c612, 0xed7aee32,
+                        0x7621b729, 0x434ee69c, 0xb03371d5, 0xd539d874,
+                        0x281fed31, 0x45fb0a51, 0x1f0ae1ac, 0x6f4d794b]);
+    }
+
+    #[test]
+    fn test_rfc7539_2_3_2() {
+        // RFC 7539, section 2.3.2: the 96-bit nonce and 32-bit counter
+        // there overlap the 64-bit counter and stream here.
+        let key = [0x03020100, 0x07060504, 0x0b0a0908, 0x0f0e0d0c,
+                   0x13121110, 0x17161514, 0x1b1a1918, 0x1f1e1d1c];
+        let mut rng: ChaChaRng = SeedableRng::from_seed(key);
+        rng.set_stream(0x4a000000);
+        rng.set_word_pos((0x09000000 << 32 | 1) * 16);
+        let v = vec::from_fn(16, |_| rng.next_u32());
+        assert_eq!(v, ~[0xe4e7f110, 0x15593bd1, 0x1fdd0f50, 0xc47120a3,
+                        0xc7f4d1c7, 0x0368c033, 0x9aaa2204, 0x4e6cd4c3,
+                        0x466482d2, 0x09aa9f07, 0x05d7c214, 0xa2028bd9,
+                        0xd19c12b5, 0xb94e16de, 0xe883d0cb, 0x4e3c50a2]);
+    }
+
+    #[test]
+    fn test_rounds() {
+        // the first words of the zero-key keystreams of ChaCha8 and
+        // ChaCha12.
+        let mut rng = ChaChaRng::from_seed_with_rounds([0, .. 8], 8);
+        assert_eq!(rng.next_u32(), 0x2fef003e);
+        let mut rng = ChaChaRng::from_seed_with_rounds([0, .. 8], 12);
+        assert_eq!(rng.next_u32(), 0x6a9af49b);
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_invalid_rounds() {
+        ChaChaRng::from_seed_with_rounds([0, .. 8], 10);
+    }
+
+    #[test]
+    fn test_fill_bytes() {
+        let mut rng: ChaChaRng = SeedableRng::from_seed([0, .. 8]);
+        let mut v = [0u8, .. 6];
+        rng.fill_bytes(v);
+        assert_eq!(v.to_owned(), ~[0x76, 0xb8, 0xe0, 0xad, 0xa0, 0xf1]);
+        // the rest of the second word is discarded.
+        assert_eq!(rng.next_u32(), 0xe56a5d40);
+    }
+
+    #[test]
+    fn test_word_pos() {
+        let mut rng: ChaChaRng = SeedableRng::from_seed([1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(rng.word_pos(), 0);
+        let v = vec::from_fn(40, |_| rng.next_u32());
+        assert_eq!(rng.word_pos(), 40);
+
+        for &pos in [0u, 5, 16, 17, 31].iter() {
+            rng.set_word_pos(pos as u64);
+            assert_eq!(rng.word_pos(), pos as u64);
+            assert_eq!(rng.next_u32(), v[pos]);
+        }
+
+        // changing the stream keeps the position.
+        rng.set_word_pos(20);
+        rng.set_stream(1);
+        assert_eq!(rng.word_pos(), 20);
+        let w = rng.next_u32();
+        assert!(w != v[20]);
+        rng.set_stream(0);
+        assert_eq!(rng.next_u32(), v[21]);
+        rng.set_stream(1);
+        rng.set_word_pos(20);
+        assert_eq!(rng.next_u32(), w);
+    }
+}
@@ -1,4 +1,5 @@
 use Rng;
+use {Error, NotReady};
 
 // Implement support for the RDRAND instruction on x86-64. Detect that
 // it is supported via `cpuinfo(eax = 1)` and checking `%ecx & (1 <
@@ -38,6 +39,10 @@ impl CPURng {
 #[cfg(target_arch="x86_64")]
 impl Rng for CPURng {
     fn next_u64(&mut self) -> u64 {
+        ::unwrap_rng(self.try_next_u64())
+    }
+
+    fn try_next_u64(&mut self) -> Result<u64, Error> {
         static NEXT_U64_ATTEMPTS: uint = 3;
 
         for _ in range(0, NEXT_U64_ATTEMPTS) {
@@ -48,9 +53,26 @@ impl Rng for CPURng {
                 asm!("rdrand $0
                      setc $1" : "=r"(rand), "=r"(ok));
             }
-            if ok == 1 { return rand; }
+            if ok == 1 { return Ok(rand); }
+        }
+        // RDRAND only fails when the hardware's entropy is
+        // temporarily exhausted, so the caller may retry later.
+        Err(Error::new(NotReady, format!("RDRAND failed {} times in a row", NEXT_U64_ATTEMPTS)))
+    }
+
+    fn try_next_u32(&mut self) -> Result<u32, Error> {
+        self.try_next_u64().map(|x| x as u32)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        let mut i = 0;
+        while i < dest.len() {
+            match self.try_next_u64() {
+                Ok(x) => i += ::write_le_bytes(dest.mut_slice_from(i), x, 8),
+                Err(e) => return Err(e)
+            }
         }
-        fail!("CPURng failed {} times in row.", NEXT_U64_ATTEMPTS)
+        Ok(())
     }
 
     // doesn't consume any entropy at the Rust level.
@@ -0,0 +1,252 @@
+use Rng;
+use SeedableRng;
+use rng::{seed, expand_seed};
+
+static TABLE_SIZE: uint = 512;
+static TABLE_MASK: uint = TABLE_SIZE - 1;
+static BLOCK_SIZE: uint = 16;
+
+/// A random number generator that uses the HC-128 stream cipher of
+/// H. Wu, [*The Stream Cipher
+/// HC-128*](http://www.ecrypt.eu.org/stream/p3ciphers/hc/hc128_p3.pdf)
+/// (2008), from the software portfolio of the eSTREAM project.
+///
+/// HC-128 keeps two tables of 512 words, which makes it slow to seed
+/// but very fast at generating bulk output, particularly on 32-bit
+/// platforms. It is suitable for cryptographic purposes, assuming it
+/// is seeded from a secure source such as `OSRng` (as `new` does).
+///
+/// The seed consists of a 128-bit key (the first four words) and a
+/// 128-bit initialisation vector (the last four).
+pub struct Hc128Rng {
+    priv p: [u32, .. TABLE_SIZE],
+    priv q: [u32, .. TABLE_SIZE],
+    // the number of steps taken, modulo 1024: `p` is updated in the
+    // first 512 of every 1024, and `q` in the rest.
+    priv counter: uint,
+    priv rsl: [u32, .. BLOCK_SIZE],
+    // the next word of `rsl` to return.
+    priv index: uint
+}
+
+#[inline]
+fn rotr(x: u32, n: u32) -> u32 { (x >> n) | (x << (32 - n)) }
+#[inline]
+fn rotl(x: u32, n: u32) -> u32 { (x << n) | (x >> (32 - n)) }
+#[inline]
+fn f1(x: u32) -> u32 { rotr(x, 7) ^ rotr(x, 18) ^ (x >> 3) }
+#[inline]
+fn f2(x: u32) -> u32 { rotr(x, 17) ^ rotr(x, 19) ^ (x >> 10) }
+
+impl Hc128Rng {
+    /// Create an HC-128 random number generator with a random key and
+    /// IV from the operating system.
+    pub fn new() -> Hc128Rng {
+        let s = unsafe { seed::<u32>(8) };
+        SeedableRng::from_seed([s[0], s[1], s[2], s[3], s[4], s[5], s[6], s[7]])
+    }
+
+    /// Create an HC-128 random number generator from a single `u64`,
+    /// expanded into a key and IV with `SplitMix64`. This is only
+    /// suitable for reproducible simulations, since the key is easily
+    /// guessed.
+    pub fn seed_from_u64(seed: u64) -> Hc128Rng {
+        let s = expand_seed::<u32>(seed, 8);
+        SeedableRng::from_seed([s[0], s[1], s[2], s[3], s[4], s[5], s[6], s[7]])
+    }
+
+    /// Update the `j`th element of `p`, returning the output word.
+    #[inline]
+    fn step_p(&mut self, j: uint) -> u32 {
+        // the `- k`s wrap, but TABLE_SIZE divides 2^uint::bits, so the
+        // mask gives `j - k` modulo 512.
+        let x = self.p[(j - 3) & TABLE_MASK];
+        let y = self.p[(j - 10) & TABLE_MASK];
+        let z = self.p[(j - 511) & TABLE_MASK];
+        self.p[j] += (rotr(x, 10) ^ rotr(z, 23)) + rotr(y, 8);
+
+        let w = self.p[(j - 12) & TABLE_MASK];
+        let h = self.q[(w & 0xff) as uint] + self.q[256 + ((w >> 16) & 0xff) as uint];
+        h ^ self.p[j]
+    }
+
+    /// Update the `j`th element of `q`, returning the output word.
+    #[inline]
+    fn step_q(&mut self, j: uint) -> u32 {
+        let x = self.q[(j - 3) & TABLE_MASK];
+        let y = self.q[(j - 10) & TABLE_MASK];
+        let z = self.q[(j - 511) & TABLE_MASK];
+        self.q[j] += (rotl(x, 10) ^ rotl(z, 23)) + rotl(y, 8);
+
+        let w = self.q[(j - 12) & TABLE_MASK];
+        let h = self.p[(w & 0xff) as uint] + self.p[256 + ((w >> 16) & 0xff) as uint];
+        h ^ self.q[j]
+    }
+
+    /// Refills the output buffer (`self.rsl`)
+    fn hc128(&mut self) {
+        // the blocks line up with the tables, so each block only
+        // updates one of them.
+        let base = self.counter & TABLE_MASK;
+        if self.counter < TABLE_SIZE {
+            for i in range(0, BLOCK_SIZE) {
+                self.rsl[i] = self.step_p(base + i);
+            }
+        } else {
+            for i in range(0, BLOCK_SIZE) {
+                self.rsl[i] = self.step_q(base + i);
+            }
+        }
+        self.counter = (self.counter + BLOCK_SIZE) & (2 * TABLE_SIZE - 1);
+        self.index = 0;
+    }
+
+    /// Initialise the tables from the key and IV in `seed`.
+    fn init(&mut self, seed: [u32, .. 8]) {
+        // the key and IV are each repeated twice, then expanded.
+        let mut w = [0u32, .. 1280];
+        for i in range(0, 8) {
+            w[i] = seed[i & 3];
+            w[i + 8] = seed[4 + (i & 3)];
+        }
+        for i in range(16, 1280) {
+            w[i] = f2(w[i - 2]) + w[i - 7] + f1(w[i - 15]) + w[i - 16] + i as u32;
+        }
+        for i in range(0, TABLE_SIZE) {
+            self.p[i] = w[i + 256];
+            self.q[i] = w[i + 768];
+        }
+
+        // run the cipher 1024 steps, replacing each table element with
+        // the output word.
+        for j in range(0, TABLE_SIZE) {
+            self.p[j] = self.step_p(j);
+        }
+        for j in range(0, TABLE_SIZE) {
+            self.q[j] = self.step_q(j);
+        }
+
+        self.counter = 0;
+        self.index = BLOCK_SIZE;
+    }
+}
+
+impl Rng for Hc128Rng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        if self.index == BLOCK_SIZE {
+            // make some more numbers
+            self.hc128();
+        }
+        let x = self.rsl[self.index];
+        self.index += 1;
+        x
+    }
+
+    #[inline]
+    fn entropy_u32(&self) -> uint { 4 }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        // in the same order as `fill_bytes`.
+        let lo = self.next_u32() as u64;
+        let hi = self.next_u32() as u64;
+        hi << 32 | lo
+    }
+
+    #[inline]
+    fn entropy_u64(&self) -> uint { 8 }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        // the words are written in order, so this gives the HC-128
+        // keystream.
+        let mut i = 0;
+        while i < dest.len() {
+            let word = self.next_u32();
+            i += ::write_le_bytes(dest.mut_slice_from(i), word as u64, 4);
+        }
+    }
+
+    #[inline]
+    fn entropy_bytes(&self, len: uint) -> uint { (len + 3) / 4 * 4 }
+}
+
+impl SeedableRng<[u32, .. 8]> for Hc128Rng {
+    /// Reseed with the key `seed[0..4]` and IV `seed[4..8]`.
+    fn reseed(&mut self, seed: [u32, .. 8]) {
+        self.init(seed);
+    }
+
+    /// Create an HC-128 random number generator with the key
+    /// `seed[0..4]` and IV `seed[4..8]`. A generator constructed with
+    /// a given seed will generate the same sequence of values as all
+    /// other generators constructed with the same seed.
+    fn from_seed(seed: [u32, .. 8]) -> Hc128Rng {
+        let mut rng = Hc128Rng {
+            p: [0, .. TABLE_SIZE],
+            q: [0, .. TABLE_SIZE],
+            counter: 0,
+            rsl: [0, .. BLOCK_SIZE],
+            index: BLOCK_SIZE
+        };
+        rng.init(seed);
+        rng
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use Rng;
+    use SeedableRng;
+    use std::vec;
+
+    // the test vectors are from the HC-128 specification.
+
+    #[test]
+    fn test_hc128_zero() {
+        let mut rng: Hc128Rng = SeedableRng::from_seed([0, 0, 0, 0, 0, 0, 0, 0]);
+        let v = vec::from_fn(16, |_| rng.next_u32());
+        assert_eq!(v, ~[0x73150082, 0x3bfd03a0, 0xfb2fd77f, 0xaa63af0e,
+                        0xde122fc6, 0xa7dc29b6, 0x62a68527, 0x8b75ec68,
+                        0x9036db1e, 0x81896005, 0x00ade078, 0x491fbf9a,
+                        0x1cdc3013, 0x6c3d6e24, 0x90f664b2, 0x9cd57102]);
+    }
+
+    #[test]
+    fn test_hc128_iv() {
+        let mut rng: Hc128Rng = SeedableRng::from_seed([0, 0, 0, 0, 1, 0, 0, 0]);
+        let v = vec::from_fn(4, |_| rng.next_u32());
+        assert_eq!(v, ~[0xc01893d5, 0xb7dbe958, 0x8f65ec98, 0x64176604]);
+    }
+
+    #[test]
+    fn test_hc128_key() {
+        let mut rng: Hc128Rng = SeedableRng::from_seed([0x55, 0, 0, 0, 0, 0, 0, 0]);
+        let v = vec::from_fn(4, |_| rng.next_u32());
+        assert_eq!(v, ~[0x518251a4, 0x04b4930a, 0xb02af931, 0x0639f032]);
+    }
+
+    #[test]
+    fn test_hc128_tables() {
+        // check the words where generation moves between the two
+        // tables, as computed by an independent implementation of the
+        // specification.
+        let mut rng: Hc128Rng = SeedableRng::from_seed([0, 0, 0, 0, 0, 0, 0, 0]);
+        let v = vec::from_fn(1026, |_| rng.next_u32());
+        assert_eq!(v.slice(512, 514).to_owned(), ~[0xb93d2aca, 0x002fc5db]);
+        assert_eq!(v.slice(1024, 1026).to_owned(), ~[0x60bbc516, 0x8559d403]);
+
+        // reseeding starts again.
+        rng.reseed([0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(rng.next_u32(), 0x73150082);
+    }
+
+    #[test]
+    fn test_hc128_fill_bytes() {
+        let mut rng: Hc128Rng = SeedableRng::from_seed([0, 0, 0, 0, 0, 0, 0, 0]);
+        let mut v = [0u8, .. 5];
+        rng.fill_bytes(v);
+        assert_eq!(v.to_owned(), ~[0x82, 0x00, 0x15, 0x73, 0xa0]);
+    }
+}
@@ -2,7 +2,7 @@
 
 use Rng;
 use SeedableRng;
-use rng::seed;
+use rng::{seed, expand_seed};
 
 
 static RAND_SIZE_LEN: uint = 8;
@@ -150,6 +150,13 @@ impl Isaac {
         let s = unsafe { seed::<u32>(RAND_SIZE) };
         SeedableRng::from_seed(s.as_slice())
     }
+
+    /// Create an ISAAC random number generator from a single `u64`,
+    /// expanded into a full seed with `SplitMix64`.
+    pub fn seed_from_u64(seed: u64) -> Isaac {
+        let s = expand_seed::<u32>(seed, RAND_SIZE);
+        SeedableRng::from_seed(s.as_slice())
+    }
 }
 
 impl Rng for Isaac {
@@ -165,6 +172,25 @@ impl Rng for Isaac {
 
     #[inline]
     fn entropy_u32(&self) -> uint { 4 }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let mut i = 0;
+        while i < dest.len() {
+            if self.cnt == 0 {
+                self.isaac();
+            }
+            // copy straight out of the buffer, in the same order as
+            // `next_u32`.
+            while self.cnt > 0 && i < dest.len() {
+                self.cnt -= 1;
+                let word = unsafe { self.rsl.unsafe_get(self.cnt) };
+                i += ::write_le_bytes(dest.mut_slice_from(i), word as u64, 4);
+            }
+        }
+    }
+
+    #[inline]
+    fn entropy_bytes(&self, len: uint) -> uint { (len + 3) / 4 * 4 }
 }
 
 trait IsaacSeed {
@@ -172,8 +198,11 @@ trait IsaacSeed {
 }
 
 impl IsaacSeed for u32 {
+    /// Reseed from a single `u32`, expanded into the full state with
+    /// `SplitMix64` (rather than padded with zeros).
     fn reseed(&self, rng: &mut Isaac) {
-        ([*self]).reseed(rng)
+        let s = expand_seed::<u32>(*self as u64, RAND_SIZE);
+        s.as_slice().reseed(rng)
     }
 }
 impl<'self> IsaacSeed for &'self [u32] {
@@ -344,6 +373,13 @@ impl Isaac64 {
         let s = unsafe { seed::<u64>(RAND_SIZE_64) };
         SeedableRng::from_seed(s.as_slice())
     }
+
+    /// Create an ISAAC-64 random number generator from a single `u64`,
+    /// expanded into a full seed with `SplitMix64`.
+    pub fn seed_from_u64(seed: u64) -> Isaac64 {
+        let s = expand_seed::<u64>(seed, RAND_SIZE_64);
+        SeedableRng::from_seed(s.as_slice())
+    }
 }
 
 impl Rng for Isaac64 {
@@ -359,14 +395,33 @@ impl Rng for Isaac64 {
 
     #[inline]
     fn entropy_u64(&self) -> uint { 8 }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let mut i = 0;
+        while i < dest.len() {
+            if self.cnt == 0 {
+                self.isaac64();
+            }
+            // copy straight out of the buffer, in the same order as
+            // `next_u64`.
+            while self.cnt > 0 && i < dest.len() {
+                self.cnt -= 1;
+                let word = unsafe { self.rsl.unsafe_get(self.cnt) };
+                i += ::write_le_bytes(dest.mut_slice_from(i), word, 8);
+            }
+        }
+    }
 }
 
 trait Isaac64Seed {
     fn reseed(&self, &mut Isaac64);
 }
 impl Isaac64Seed for u64 {
+    /// Reseed from a single `u64`, expanded into the full state with
+    /// `SplitMix64` (rather than padded with zeros).
     fn reseed(&self, rng: &mut Isaac64) {
-        rng.reseed(&[*self])
+        let s = expand_seed::<u64>(*self, RAND_SIZE_64);
+        s.as_slice().reseed(rng)
     }
 }
 impl<'self> Isaac64Seed for &'self [u64] {
@@ -1,6 +1,6 @@
 use Rng;
 use SeedableRng;
-use rng::seed;
+use rng::{seed, expand_seed};
 
 pub struct MinStd_Rand {
     priv x: u32
@@ -9,6 +9,13 @@ impl MinStd_Rand {
     pub fn new() -> MinStd_Rand {
         SeedableRng::from_seed(unsafe {seed(1)}[0])
     }
+
+    /// Create a MINSTD generator from a single `u64`, mixed with
+    /// `SplitMix64` and reduced to a valid (non-zero) state.
+    pub fn seed_from_u64(seed: u64) -> MinStd_Rand {
+        let s = expand_seed::<u32>(seed, 1);
+        SeedableRng::from_seed(s[0] % 2147483646 + 1)
+    }
 }
 
 impl Rng for MinStd_Rand {
@@ -41,6 +48,12 @@ impl Rand48 {
     pub fn new() -> Rand48 {
         SeedableRng::from_seed(unsafe {seed(1)}[0])
     }
+
+    /// Create a `rand48` generator from a single `u64`, mixed with
+    /// `SplitMix64`.
+    pub fn seed_from_u64(seed: u64) -> Rand48 {
+        SeedableRng::from_seed(expand_seed::<u32>(seed, 1)[0])
+    }
 }
 
 impl Rng for Rand48 {
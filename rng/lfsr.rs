@@ -1,6 +1,6 @@
 use Rng;
 use SeedableRng;
-use rng::seed;
+use rng::{seed, expand_seed};
 
 macro_rules! step{
     ($thing:expr, $s1:expr, $s2:expr, $and:expr, $s3:expr) => {{
@@ -13,14 +13,23 @@ macro_rules! step{
 pub struct LFSR258 {
     priv z1: u64, priv z2: u64, priv z3: u64, priv z4: u64, priv z5: u64
 }
-// TODO: seeds
 
 /// Minimum values of the seeds of a LFSR258 generator
 static LFSR258_LIMITS: [u64, .. 5] = [1, 511, 4095, 131071, 8388607];
 impl LFSR258 {
     pub fn new() -> LFSR258 {
+        LFSR258::from_raw_seed(unsafe { seed::<u64>(5) })
+    }
+
+    /// Create a LFSR258 generator from a single `u64`, expanded into a
+    /// full seed with `SplitMix64` and adjusted to satisfy the minimum
+    /// seed values.
+    pub fn seed_from_u64(seed: u64) -> LFSR258 {
+        LFSR258::from_raw_seed(expand_seed(seed, 5))
+    }
+
+    fn from_raw_seed(rand: &[u64]) -> LFSR258 {
         let mut s = [0, .. 5];
-        let rand = unsafe { seed::<u64>(5) };
         for i in range(0, 5) {
             // force every seed value to be at least as large as the
             // minimums, by zeroing the high bit and adding the minimum
@@ -76,10 +85,27 @@ pub struct LFSR113 {
     priv z4: u32
 }
 
+/// Minimum values of the seeds of a LFSR113 generator
+static LFSR113_LIMITS: [u32, .. 4] = [2, 8, 16, 128];
 impl LFSR113 {
     pub fn new() -> LFSR113 {
-        let seed = unsafe { seed::<u32>(4) };
-        SeedableRng::from_seed([seed[0], seed[1], seed[2], seed[3]])
+        LFSR113::from_raw_seed(unsafe { seed::<u32>(4) })
+    }
+
+    /// Create a LFSR113 generator from a single `u64`, expanded into a
+    /// full seed with `SplitMix64` and adjusted to satisfy the minimum
+    /// seed values.
+    pub fn seed_from_u64(seed: u64) -> LFSR113 {
+        LFSR113::from_raw_seed(expand_seed(seed, 4))
+    }
+
+    fn from_raw_seed(rand: &[u32]) -> LFSR113 {
+        let mut s = [0, .. 4];
+        for i in range(0, 4) {
+            // as for LFSR258
+            s[i] = (rand[i] >> 1) + LFSR113_LIMITS[i];
+        }
+        SeedableRng::from_seed(s)
     }
 }
 
@@ -122,10 +148,27 @@ pub struct Taus88 {
     priv s3: u32
 }
 
+/// Minimum values of the seeds of a Taus88 generator
+static TAUS88_LIMITS: [u32, .. 3] = [2, 8, 16];
 impl Taus88 {
-     pub fn new() -> Taus88 { // TODO: seeds?
-         let seed = unsafe { seed::<u32>(3) };
-         SeedableRng::from_seed([seed[0], seed[1], seed[2]])
+    pub fn new() -> Taus88 {
+        Taus88::from_raw_seed(unsafe { seed::<u32>(3) })
+    }
+
+    /// Create a Taus88 generator from a single `u64`, expanded into a
+    /// full seed with `SplitMix64` and adjusted to satisfy the minimum
+    /// seed values.
+    pub fn seed_from_u64(seed: u64) -> Taus88 {
+        Taus88::from_raw_seed(expand_seed(seed, 3))
+    }
+
+    fn from_raw_seed(rand: &[u32]) -> Taus88 {
+        let mut s = [0, .. 3];
+        for i in range(0, 3) {
+            // as for LFSR258
+            s[i] = (rand[i] >> 1) + TAUS88_LIMITS[i];
+        }
+        SeedableRng::from_seed(s)
     }
 }
 
@@ -1,5 +1,5 @@
 use std::cmp;
-use rng::seed;
+use rng::{seed, expand_seed};
 use Rng;
 use SeedableRng;
 
@@ -44,6 +44,14 @@ impl MT19937 {
         let s = unsafe { seed::<u32>(MT_N) };
         SeedableRng::from_seed(s.as_slice())
     }
+
+    /// Create a Mersenne Twister from a single `u64`, expanded into a
+    /// full seed with `SplitMix64`. Unlike seeding with a `u32`, which
+    /// follows the reference `init_genrand`, this uses all 64 bits.
+    pub fn seed_from_u64(seed: u64) -> MT19937 {
+        let s = expand_seed::<u32>(seed, MT_N);
+        SeedableRng::from_seed(s.as_slice())
+    }
 }
 
 impl Rng for MT19937 {
@@ -53,17 +61,43 @@ impl Rng for MT19937 {
             self.generate_numbers();
         }
 
-        let mut y = unsafe { self.state.unsafe_get(self.index) };
+        let y = unsafe { self.state.unsafe_get(self.index) };
         self.index += 1;
-
-        y ^= y >> 11;
-        y ^= (y << 7) & 0x9d2c5680;
-        y ^= (y << 15) & 0xefc60000;
-        y ^ (y >> 18)
+        temper(y)
     }
 
     #[inline]
     fn entropy_u32(&self) -> uint { 4 }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        let mut i = 0;
+        while i < dest.len() {
+            if self.index >= MT_N {
+                self.generate_numbers();
+            }
+            // temper the state words directly, rather than going
+            // through `next_u32` for each one.
+            while self.index < MT_N && i < dest.len() {
+                let y = unsafe { self.state.unsafe_get(self.index) };
+                self.index += 1;
+                i += ::write_le_bytes(dest.mut_slice_from(i), temper(y) as u64, 4);
+            }
+        }
+    }
+
+    #[inline]
+    fn entropy_bytes(&self, len: uint) -> uint { (len + 3) / 4 * 4 }
+}
+
+/// The tempering transform applied to each word of the MT19937 state
+/// to produce its output.
+#[inline]
+fn temper(y: u32) -> u32 {
+    let mut y = y;
+    y ^= y >> 11;
+    y ^= (y << 7) & 0x9d2c5680;
+    y ^= (y << 15) & 0xefc60000;
+    y ^ (y >> 18)
 }
 
 trait MT19937Seed { fn reseed(&self, &mut MT19937); }
@@ -159,6 +193,13 @@ impl MT19937_64 {
         let s = unsafe { seed::<u64>(MT64_N) };
         SeedableRng::from_seed(s.as_slice())
     }
+
+    /// Create a 64-bit Mersenne Twister from a single `u64`, expanded
+    /// into a full seed with `SplitMix64`.
+    pub fn seed_from_u64(seed: u64) -> MT19937_64 {
+        let s = expand_seed::<u64>(seed, MT64_N);
+        SeedableRng::from_seed(s.as_slice())
+    }
 }
 
 impl Rng for MT19937_64 {
@@ -246,6 +287,13 @@ impl WELL512 {
         let s = unsafe { seed::<u32>(WELL512_N) };
         SeedableRng::from_seed(s.as_slice())
     }
+
+    /// Create a WELL512 generator from a single `u64`, expanded into a
+    /// full seed with `SplitMix64`.
+    pub fn seed_from_u64(seed: u64) -> WELL512 {
+        let s = expand_seed::<u32>(seed, WELL512_N);
+        SeedableRng::from_seed(s.as_slice())
+    }
 }
 
 impl Rng for WELL512 {
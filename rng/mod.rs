@@ -2,12 +2,16 @@ use Rng;
 use SeedableRng;
 use std::{cast, vec, mem};
 
-pub use self::xorshift::XorShift4;
+pub use self::xorshift::{XorShift4, Xoshiro256StarStar, Xoshiro256Plus, Xoroshiro128Plus};
 pub use self::mersenne_twister::{MT19937, MT19937_64, WELL512};
 pub use self::isaac::{Isaac, Isaac64};
 pub use self::lcg::{MinStd_Rand, Rand48};
 pub use self::lfsr::{LFSR258, LFSR113, Taus88};
 pub use self::mwc::{CMWC, MWC256};
+pub use self::pcg::{Pcg32, Pcg64};
+pub use self::splitmix::{SplitMix64, expand_seed};
+pub use self::chacha::ChaChaRng;
+pub use self::hc128::Hc128Rng;
 pub use self::os::OSRng;
 pub use self::hardware::CPURng;
 pub use self::reseeding::ReseedingRng;
@@ -18,6 +22,10 @@ pub mod isaac;
 pub mod lcg;
 pub mod mwc;
 pub mod lfsr;
+pub mod pcg;
+pub mod splitmix;
+pub mod chacha;
+pub mod hc128;
 pub mod reseeding;
 
 #[cfg(win32)]
@@ -35,31 +43,84 @@ pub unsafe fn seed<T>(len: uint) -> ~[T] {
     let byte_size = len * mem::nonzero_size_of::<T>();
     let mut vec = vec::from_elem(byte_size, 0u8);
 
-    let rng: OSRng = OSRng::new();
-    rng.fill_vec(vec);
+    let mut rng: OSRng = OSRng::new();
+    rng.fill_bytes(vec);
 
     cast::transmute(vec)
 }
 
 /// The standard RNG. This is designed to be efficient on the current
 /// platform.
-#[cfg(not(target_word_size="64"))]
+///
+/// This is ISAAC (or ISAAC-64 on 64-bit targets) by default. Building
+/// with `--cfg 'std_rng="chacha"'` uses `ChaChaRng` instead, whose
+/// security is much better studied, and on 32-bit targets `--cfg
+/// 'std_rng="hc128"'` uses the faster `Hc128Rng`.
+#[cfg(not(std_rng="chacha"), not(std_rng="hc128"), not(target_word_size="64"))]
 pub struct StdRng { priv rng: Isaac }
 
 /// The standard RNG. This is designed to be efficient on the current
 /// platform.
-#[cfg(target_word_size="64")]
+///
+/// This is ISAAC (or ISAAC-64 on 64-bit targets) by default. Building
+/// with `--cfg 'std_rng="chacha"'` uses `ChaChaRng` instead, whose
+/// security is much better studied, and on 32-bit targets `--cfg
+/// 'std_rng="hc128"'` uses the faster `Hc128Rng`.
+#[cfg(not(std_rng="chacha"), target_word_size="64")]
 pub struct StdRng { priv rng: Isaac64 }
 
+/// The standard RNG, using `ChaChaRng` (with 20 rounds).
+#[cfg(std_rng="chacha")]
+pub struct StdRng { priv rng: ChaChaRng }
+
+/// The standard RNG, using `Hc128Rng`.
+#[cfg(not(std_rng="chacha"), std_rng="hc128", not(target_word_size="64"))]
+pub struct StdRng { priv rng: Hc128Rng }
+
+impl StdRng {
+    /// Create the standard RNG from a single `u64`, expanded into a
+    /// full seed with `SplitMix64`.
+    #[cfg(not(std_rng="chacha"), not(std_rng="hc128"), not(target_word_size="64"))]
+    pub fn seed_from_u64(seed: u64) -> StdRng {
+        StdRng { rng: Isaac::seed_from_u64(seed) }
+    }
+    /// Create the standard RNG from a single `u64`, expanded into a
+    /// full seed with `SplitMix64`.
+    #[cfg(not(std_rng="chacha"), target_word_size="64")]
+    pub fn seed_from_u64(seed: u64) -> StdRng {
+        StdRng { rng: Isaac64::seed_from_u64(seed) }
+    }
+    /// Create the standard RNG from a single `u64`, expanded into a
+    /// full seed with `SplitMix64`.
+    #[cfg(std_rng="chacha")]
+    pub fn seed_from_u64(seed: u64) -> StdRng {
+        StdRng { rng: ChaChaRng::seed_from_u64(seed) }
+    }
+    /// Create the standard RNG from a single `u64`, expanded into a
+    /// full seed with `SplitMix64`.
+    #[cfg(not(std_rng="chacha"), std_rng="hc128", not(target_word_size="64"))]
+    pub fn seed_from_u64(seed: u64) -> StdRng {
+        StdRng { rng: Hc128Rng::seed_from_u64(seed) }
+    }
+}
+
 impl Default for StdRng {
-    #[cfg(not(target_word_size="64"))]
+    #[cfg(not(std_rng="chacha"), not(std_rng="hc128"), not(target_word_size="64"))]
     fn default() -> StdRng {
         StdRng { rng: Isaac::new() }
     }
-    #[cfg(target_word_size="64")]
+    #[cfg(not(std_rng="chacha"), target_word_size="64")]
     fn default() -> StdRng {
         StdRng { rng: Isaac64::new() }
     }
+    #[cfg(std_rng="chacha")]
+    fn default() -> StdRng {
+        StdRng { rng: ChaChaRng::new() }
+    }
+    #[cfg(not(std_rng="chacha"), std_rng="hc128", not(target_word_size="64"))]
+    fn default() -> StdRng {
+        StdRng { rng: Hc128Rng::new() }
+    }
 }
 
 impl Rng for StdRng {
@@ -80,32 +141,79 @@ impl Rng for StdRng {
     fn entropy_u64(&self) -> uint {
         self.rng.entropy_u64()
     }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+    #[inline]
+    fn entropy_bytes(&self, len: uint) -> uint {
+        self.rng.entropy_bytes(len)
+    }
 }
 
 pub trait StdSeed {
     fn reseed(&self, &mut StdRng);
 }
 impl StdSeed for uint {
-    #[cfg(not(target_word_size="64"))]
+    #[cfg(not(std_rng="chacha"), not(std_rng="hc128"), not(target_word_size="64"))]
     fn reseed(&self, rng: &mut StdRng) {
         rng.rng.reseed(*self as u32)
     }
-    #[cfg(target_word_size="64")]
+    #[cfg(not(std_rng="chacha"), target_word_size="64")]
     fn reseed(&self, rng: &mut StdRng) {
         rng.rng.reseed(*self as u64)
     }
+    #[cfg(std_rng="chacha")]
+    fn reseed(&self, rng: &mut StdRng) {
+        rng.rng = ChaChaRng::seed_from_u64(*self as u64)
+    }
+    #[cfg(not(std_rng="chacha"), std_rng="hc128", not(target_word_size="64"))]
+    fn reseed(&self, rng: &mut StdRng) {
+        rng.rng = Hc128Rng::seed_from_u64(*self as u64)
+    }
 }
 impl<'self> StdSeed for &'self [uint] {
-    #[cfg(not(target_word_size="64"))]
+    #[cfg(not(std_rng="chacha"), not(std_rng="hc128"), not(target_word_size="64"))]
     fn reseed(&self, rng: &mut StdRng) {
         let seed: &[u32] = unsafe {cast::transmute(*self)};
         rng.rng.reseed(seed)
     }
-    #[cfg(target_word_size="64")]
+    #[cfg(not(std_rng="chacha"), target_word_size="64")]
     fn reseed(&self, rng: &mut StdRng) {
         let seed: &[u64] = unsafe {cast::transmute(*self)};
         rng.rng.reseed(seed);
     }
+    /// Use the first 256 bits of the seed as the key, padding with
+    /// zeros.
+    #[cfg(std_rng="chacha")]
+    fn reseed(&self, rng: &mut StdRng) {
+        rng.rng = SeedableRng::from_seed(seed_words(*self));
+    }
+    /// Use the first 256 bits of the seed as the key and IV, padding
+    /// with zeros.
+    #[cfg(not(std_rng="chacha"), std_rng="hc128", not(target_word_size="64"))]
+    fn reseed(&self, rng: &mut StdRng) {
+        rng.rng = SeedableRng::from_seed(seed_words(*self));
+    }
+}
+
+/// The first 256 bits of `seed`, as little-endian `u32`s, padded with
+/// zeros.
+fn seed_words(seed: &[uint]) -> [u32, .. 8] {
+    let mut words = [0u32, .. 8];
+    let mut i = 0;
+    for &x in seed.iter() {
+        let mut x = x as u64;
+        for _ in range(0, mem::size_of::<uint>() / 4) {
+            if i < words.len() {
+                words[i] = x as u32;
+                i += 1;
+            }
+            x >>= 32;
+        }
+    }
+    words
 }
 
 impl<Seed: StdSeed> SeedableRng<Seed> for StdRng {
@@ -168,6 +276,9 @@ mod bench {
     }
 
     bench_rng! { XorShift4 }
+    bench_rng! { Xoshiro256StarStar }
+    bench_rng! { Xoshiro256Plus }
+    bench_rng! { Xoroshiro128Plus }
 
     bench_rng! { Isaac }
     bench_rng! { Isaac64 }
@@ -187,4 +298,12 @@ mod bench {
 
     bench_rng! { MinStd_Rand }
     bench_rng! { Rand48 }
+
+    bench_rng! { Pcg32 }
+    bench_rng! { Pcg64 }
+
+    bench_rng! { SplitMix64 }
+
+    bench_rng! { ChaChaRng }
+    bench_rng! { Hc128Rng }
 }
@@ -1,4 +1,5 @@
 use Rng;
+use rng::expand_seed;
 
 static CMWC_PHI: u32 = 0x9e3779b9;
 static CMWC_N : uint = 4096;
@@ -24,6 +25,16 @@ impl CMWC {
 
         r
     }
+
+    /// Create a CMWC generator whose lag table is filled from a single
+    /// `u64` with `SplitMix64`.
+    pub fn seed_from_u64(seed: u64) -> CMWC {
+        let mut r = CMWC { q: [0, .. CMWC_N], c: 362436, i: CMWC_N - 1 };
+        for (q, &x) in r.q.mut_iter().zip(expand_seed::<u32>(seed, CMWC_N).iter()) {
+            *q = x;
+        }
+        r
+    }
 }
 
 impl Rng for CMWC {
@@ -63,6 +74,16 @@ impl MWC256 {
             i: 255
         }
     }
+
+    /// Create a MWC256 generator whose lag table is filled from a single
+    /// `u64` with `SplitMix64`.
+    pub fn seed_from_u64(seed: u64) -> MWC256 {
+        let mut r = MWC256::new();
+        for (q, &x) in r.q.mut_iter().zip(expand_seed::<u32>(seed, MWC256_N).iter()) {
+            *q = x;
+        }
+        r
+    }
 }
 
 impl Rng for MWC256 {
@@ -1,40 +1,91 @@
 use std::{io};
+use {Error, Unavailable, Unexpected};
 
 // these don't consume any entropy at the Rust level, hence just set
 // entropy_* == 0.
 
+/// Open the random device at `path`.
+fn open(path: &'static str) -> Result<@io::Reader, Error> {
+    match io::file_reader(&Path::new(path)) {
+        Err(e) => Err(Error::new(Unavailable, format!("error opening {}: {}", path, e))),
+        Ok(reader) => Ok(reader)
+    }
+}
+
+/// Fill `dest` from `handle`, which reads from the device at `path`,
+/// returning an error if it runs out of data.
+fn read_bytes(handle: @io::Reader, path: &'static str, dest: &mut [u8]) -> Result<(), Error> {
+    let len = dest.len();
+    let mut filled = 0;
+    while filled < len {
+        let n = handle.read(dest.mut_slice_from(filled), len - filled);
+        if n == 0 {
+            return Err(Error::new(Unexpected,
+                                  format!("short read from {}: got {} of {} bytes",
+                                          path, filled, len)));
+        }
+        filled += n;
+    }
+    Ok(())
+}
+
+/// Read a little-endian `u64` of `size` bytes from `handle`.
+fn read_le(handle: @io::Reader, path: &'static str, size: uint) -> Result<u64, Error> {
+    let mut buf = [0u8, .. 8];
+    match read_bytes(handle, path, buf.mut_slice_to(size)) {
+        Err(e) => Err(e),
+        Ok(()) => Ok(buf.iter().rev().fold(0u64, |x, &b| x << 8 | b as u64))
+    }
+}
+
+static URANDOM: &'static str = "/dev/urandom";
+static RANDOM: &'static str = "/dev/random";
+
 pub struct OSRng {
     handle: @io::Reader
 }
 
 impl OSRng {
+    /// Create an RNG reading from `/dev/urandom`, failing if it cannot
+    /// be opened.
     pub fn new() -> OSRng {
-        match io::file_reader(&Path::new("/dev/urandom")) {
-            Err(e) => fail!("error opening /dev/urandom: {}", e),
-            Ok(urandom) => OSRng { handle: urandom }
-        }
+        ::unwrap_rng(OSRng::try_new())
+    }
+
+    /// Create an RNG reading from `/dev/urandom`, returning an error
+    /// if it cannot be opened.
+    pub fn try_new() -> Result<OSRng, Error> {
+        open(URANDOM).map(|urandom| OSRng { handle: urandom })
     }
 }
 
 impl ::Rng for OSRng {
     fn next_u32(&mut self) -> u32 {
-        self.handle.read_le_u32()
+        ::unwrap_rng(self.try_next_u32())
     }
     fn next_u64(&mut self) -> u64 {
-        self.handle.read_le_u64()
+        ::unwrap_rng(self.try_next_u64())
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        ::unwrap_rng(self.try_fill_bytes(dest))
+    }
+
+    fn try_next_u32(&mut self) -> Result<u32, Error> {
+        read_le(self.handle, URANDOM, 4).map(|x| x as u32)
+    }
+    fn try_next_u64(&mut self) -> Result<u64, Error> {
+        read_le(self.handle, URANDOM, 8)
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        read_bytes(self.handle, URANDOM, dest)
     }
 
     #[inline]
     fn entropy_u32(&self) -> uint { 0 }
     #[inline]
     fn entropy_u64(&self) -> uint { 0 }
-}
-
-impl OSRng {
-    pub fn fill_vec(&self, vec: &mut [u8]) {
-        let len = vec.len();
-        self.handle.read(vec, len);
-    }
+    #[inline]
+    fn entropy_bytes(&self, _len: uint) -> uint { 0 }
 }
 
 pub struct OSSecureRng {
@@ -42,24 +93,44 @@ pub struct OSSecureRng {
 }
 
 impl OSSecureRng {
+    /// Create an RNG reading from `/dev/random`, failing if it cannot
+    /// be opened.
     pub fn new() -> OSSecureRng {
-        match io::file_reader(&Path::new("/dev/random")) {
-            Err(e) => fail!("error opening /dev/random: {}", e),
-            Ok(random) => OSSecureRng { handle: random }
-        }
+        ::unwrap_rng(OSSecureRng::try_new())
+    }
+
+    /// Create an RNG reading from `/dev/random`, returning an error if
+    /// it cannot be opened.
+    pub fn try_new() -> Result<OSSecureRng, Error> {
+        open(RANDOM).map(|random| OSSecureRng { handle: random })
     }
 }
 
 impl ::Rng for OSSecureRng {
     fn next_u32(&mut self) -> u32 {
-        self.handle.read_le_u32()
+        ::unwrap_rng(self.try_next_u32())
     }
     fn next_u64(&mut self) -> u64 {
-        self.handle.read_le_u64()
+        ::unwrap_rng(self.try_next_u64())
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        ::unwrap_rng(self.try_fill_bytes(dest))
+    }
+
+    fn try_next_u32(&mut self) -> Result<u32, Error> {
+        read_le(self.handle, RANDOM, 4).map(|x| x as u32)
+    }
+    fn try_next_u64(&mut self) -> Result<u64, Error> {
+        read_le(self.handle, RANDOM, 8)
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        read_bytes(self.handle, RANDOM, dest)
     }
 
     #[inline]
     fn entropy_u32(&self) -> uint { 0 }
     #[inline]
     fn entropy_u64(&self) -> uint { 0 }
+    #[inline]
+    fn entropy_bytes(&self, _len: uint) -> uint { 0 }
 }
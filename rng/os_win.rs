@@ -1,4 +1,5 @@
 use std::libc::{GetLastError, FALSE};
+use {Error, Unavailable, Unexpected};
 
 #[abi = "cdecl"]
 mod raw {
@@ -19,19 +20,62 @@ pub struct OSRng {
     priv hcryptprov: HCRYPTPROV
 }
 
-impl Rng for OSRng {
+impl OSRng {
+    /// Create an RNG using the system cryptographic provider, failing
+    /// if it cannot be acquired.
     pub fn new() -> OSRng {
+        ::unwrap_rng(OSRng::try_new())
+    }
 
+    /// Create an RNG using the system cryptographic provider,
+    /// returning an error if it cannot be acquired.
+    pub fn try_new() -> Result<OSRng, Error> {
         let hcp = ptr::mut_null();
         // TODO these two 0 constants are incorrect!
-        if unsafe { CryptAcquireContext(hcp, ptr::null(), ptr::null(), 0, 0); } == FALSE {
-            fail!("CryptAcquireContext failed with error %u", unsafe {GetLastError()})
+        if unsafe { CryptAcquireContext(hcp, ptr::null(), ptr::null(), 0, 0) } == FALSE {
+            return Err(Error::new(Unavailable,
+                                  format!("CryptAcquireContext failed with error {}",
+                                          unsafe {GetLastError()})));
         }
 
-        OSRng { hcryptprov: hcp }
+        Ok(OSRng { hcryptprov: hcp })
+    }
+}
+
+impl Rng for OSRng {
+    fn next_u32(&mut self) -> u32 {
+        ::unwrap_rng(self.try_next_u32())
+    }
+    fn next_u64(&mut self) -> u64 {
+        ::unwrap_rng(self.try_next_u64())
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        ::unwrap_rng(self.try_fill_bytes(dest))
+    }
+
+    fn try_next_u32(&mut self) -> Result<u32, Error> {
+        let mut buf = [0u8, .. 4];
+        do self.try_fill_bytes(buf).map |_| {
+            buf.iter().rev().fold(0u32, |x, &b| x << 8 | b as u32)
+        }
+    }
+    fn try_next_u64(&mut self) -> Result<u64, Error> {
+        let mut buf = [0u8, .. 8];
+        do self.try_fill_bytes(buf).map |_| {
+            buf.iter().rev().fold(0u64, |x, &b| x << 8 | b as u64)
+        }
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        if dest.is_empty() {
+            return Ok(());
+        }
+        if unsafe {CryptGenRandom(self.hcryptprov, dest.len(), dest.unsafe_mut_ref(0))} == FALSE {
+            return Err(Error::new(Unexpected,
+                                  format!("CryptGenRandom failed with error {}",
+                                          unsafe {GetLastError()})));
+        }
+        Ok(())
     }
-    fn next_u32(&mut self) -> u32 { 0 }
-    fn next_u64(&mut self) -> u64 { 0 }
 
     #[inline]
     fn entropy_u32(&self) -> uint { 4 }
@@ -48,23 +92,25 @@ impl Drop for OSRng {
     }
 }
 
-impl OSRng {
-    pub fn fill_vec(&self, vec: &mut [u8]) {
-        if unsafe {CryptGenRandom(self.hcryptprov, vec.len(), vec.unsafe_mut_ref(0))} == FALSE {
-            fail!("CryptGenRandom failed with error %u", unsafe {GetLastError()})
-        }
-    }
-}
-
 pub struct OSSecureRng {
     priv inner: OSRng
 }
 
-impl Rng for OSSecureRng {
+impl OSSecureRng {
+    /// Create an RNG using the system cryptographic provider, failing
+    /// if it cannot be acquired.
     pub fn new() -> OSSecureRng {
         OSSecureRng { inner: OSRng::new() }
     }
 
+    /// Create an RNG using the system cryptographic provider,
+    /// returning an error if it cannot be acquired.
+    pub fn try_new() -> Result<OSSecureRng, Error> {
+        OSRng::try_new().map(|inner| OSSecureRng { inner: inner })
+    }
+}
+
+impl Rng for OSSecureRng {
     fn next_u32(&mut self) -> u32 {
         self.inner.next_u32()
     }
@@ -73,6 +119,20 @@ impl Rng for OSSecureRng {
         self.inner.next_u64()
     }
 
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.inner.fill_bytes(dest)
+    }
+
+    fn try_next_u32(&mut self) -> Result<u32, Error> {
+        self.inner.try_next_u32()
+    }
+    fn try_next_u64(&mut self) -> Result<u64, Error> {
+        self.inner.try_next_u64()
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.inner.try_fill_bytes(dest)
+    }
+
     #[inline]
     fn entropy_u32(&self) -> uint { 4 }
     #[inline]
@@ -0,0 +1,234 @@
+use Rng;
+use SeedableRng;
+use rng::{seed, expand_seed};
+
+static PCG32_MULTIPLIER: u64 = 6364136223846793005;
+
+/// The 32-bit [PCG](http://www.pcg-random.org/) random number
+/// generator, PCG-XSH-RR: a 64-bit linear congruential generator with
+/// its output permuted by an xorshift and a random rotation. Not
+/// suitable for cryptographic purposes.
+///
+/// The seed is a pair `(state, stream)`. Generators with different
+/// `stream`s produce independent sequences, even from the same
+/// `state`; only the low 63 bits of `stream` are used.
+pub struct Pcg32 {
+    priv state: u64,
+    // always odd.
+    priv inc: u64
+}
+
+impl Pcg32 {
+    /// Create a PCG32 generator with a random seed.
+    pub fn new() -> Pcg32 {
+        let s = unsafe { seed::<u64>(2) };
+        SeedableRng::from_seed((s[0], s[1]))
+    }
+
+    /// Create a PCG32 generator from a single `u64`, expanded into a
+    /// state and stream with `SplitMix64`.
+    pub fn seed_from_u64(seed: u64) -> Pcg32 {
+        let s = expand_seed::<u64>(seed, 2);
+        SeedableRng::from_seed((s[0], s[1]))
+    }
+
+    #[inline]
+    fn step(&mut self) {
+        self.state = self.state * PCG32_MULTIPLIER + self.inc;
+    }
+}
+
+impl Rng for Pcg32 {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.step();
+
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rot = (old >> 59) as u32;
+        (xorshifted >> rot) | (xorshifted << ((32 - rot) & 31))
+    }
+
+    #[inline]
+    fn entropy_u32(&self) -> uint { 4 }
+}
+
+impl SeedableRng<(u64, u64)> for Pcg32 {
+    fn reseed(&mut self, (state, stream): (u64, u64)) {
+        // as in the reference implementation, so that the outputs
+        // match.
+        self.state = 0;
+        self.inc = (stream << 1) | 1;
+        self.step();
+        self.state += state;
+        self.step();
+    }
+
+    /// Create a PCG32 generator with the seed `(state, stream)`. A
+    /// generator constructed with a given seed will generate the same
+    /// sequence of values as all other generators constructed with the
+    /// same seed.
+    fn from_seed(seed: (u64, u64)) -> Pcg32 {
+        let mut r = Pcg32 { state: 0, inc: 1 };
+        r.reseed(seed);
+        r
+    }
+}
+
+static PCG64_MULTIPLIER_HI: u64 = 0x2360ed051fc65da4;
+static PCG64_MULTIPLIER_LO: u64 = 0x4385df649fccf645;
+
+/// The full 128-bit product of `a` and `b`, as `(high, low)`.
+#[inline]
+fn mul_wide(a: u64, b: u64) -> (u64, u64) {
+    let (a_hi, a_lo) = (a >> 32, a & 0xffff_ffff);
+    let (b_hi, b_lo) = (b >> 32, b & 0xffff_ffff);
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+    // at most 2^64 - 1, so this can't overflow.
+    let cross = (lo_lo >> 32) + (hi_lo & 0xffff_ffff) + lo_hi;
+    (hi_hi + (hi_lo >> 32) + (cross >> 32), (cross << 32) | (lo_lo & 0xffff_ffff))
+}
+
+/// The 64-bit [PCG](http://www.pcg-random.org/) random number
+/// generator, PCG-XSL-RR 128/64: a 128-bit linear congruential
+/// generator, with the two halves of its state xored together and
+/// randomly rotated for output. The 128-bit arithmetic is done on
+/// pairs of `u64`s. Not suitable for cryptographic purposes.
+///
+/// The seed is a pair `(state, stream)`, as for `Pcg32`.
+pub struct Pcg64 {
+    priv state_hi: u64,
+    priv state_lo: u64,
+    // always odd.
+    priv inc_hi: u64,
+    priv inc_lo: u64
+}
+
+impl Pcg64 {
+    /// Create a PCG64 generator with a random seed.
+    pub fn new() -> Pcg64 {
+        let s = unsafe { seed::<u64>(2) };
+        SeedableRng::from_seed((s[0], s[1]))
+    }
+
+    /// Create a PCG64 generator from a single `u64`, expanded into a
+    /// state and stream with `SplitMix64`.
+    pub fn seed_from_u64(seed: u64) -> Pcg64 {
+        let s = expand_seed::<u64>(seed, 2);
+        SeedableRng::from_seed((s[0], s[1]))
+    }
+
+    #[inline]
+    fn step(&mut self) {
+        // state * multiplier, modulo 2^128.
+        let (hi, lo) = mul_wide(self.state_lo, PCG64_MULTIPLIER_LO);
+        let hi = hi + self.state_hi * PCG64_MULTIPLIER_LO + self.state_lo * PCG64_MULTIPLIER_HI;
+        let (inc_hi, inc_lo) = (self.inc_hi, self.inc_lo);
+        self.add(hi, lo, inc_hi, inc_lo);
+    }
+
+    /// Set the state to `(hi, lo) + (add_hi, add_lo)`, modulo 2^128.
+    #[inline]
+    fn add(&mut self, hi: u64, lo: u64, add_hi: u64, add_lo: u64) {
+        let lo = lo + add_lo;
+        let carry = if lo < add_lo { 1 } else { 0 };
+        self.state_hi = hi + add_hi + carry;
+        self.state_lo = lo;
+    }
+}
+
+impl Rng for Pcg64 {
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.step();
+        let x = self.state_hi ^ self.state_lo;
+        let rot = self.state_hi >> 58;
+        (x >> rot) | (x << ((64 - rot) & 63))
+    }
+
+    #[inline]
+    fn entropy_u64(&self) -> uint { 8 }
+}
+
+impl SeedableRng<(u64, u64)> for Pcg64 {
+    fn reseed(&mut self, (state, stream): (u64, u64)) {
+        // as in the reference implementation, so that the outputs
+        // match.
+        self.state_hi = 0;
+        self.state_lo = 0;
+        self.inc_hi = stream >> 63;
+        self.inc_lo = (stream << 1) | 1;
+        self.step();
+        let (hi, lo) = (self.state_hi, self.state_lo);
+        self.add(hi, lo, 0, state);
+        self.step();
+    }
+
+    /// Create a PCG64 generator with the seed `(state, stream)`. A
+    /// generator constructed with a given seed will generate the same
+    /// sequence of values as all other generators constructed with the
+    /// same seed.
+    fn from_seed(seed: (u64, u64)) -> Pcg64 {
+        let mut r = Pcg64 { state_hi: 0, state_lo: 0, inc_hi: 0, inc_lo: 1 };
+        r.reseed(seed);
+        r
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use super::mul_wide;
+    use Rng;
+    use SeedableRng;
+
+    #[test]
+    fn test_pcg32_reference() {
+        // from the `pcg32-demo` program of the reference C
+        // implementation.
+        let mut rng: Pcg32 = SeedableRng::from_seed((42u64, 54u64));
+        let v = ~[rng.next_u32(), rng.next_u32(), rng.next_u32(),
+                  rng.next_u32(), rng.next_u32(), rng.next_u32()];
+        assert_eq!(v, ~[0xa15c02b7, 0x7b47f409, 0xba1d3330,
+                        0x83d2f293, 0xbfa4784b, 0xcbed606e]);
+    }
+
+    #[test]
+    fn test_pcg64_reference() {
+        // from the `pcg64-demo` program of the reference C
+        // implementation.
+        let mut rng: Pcg64 = SeedableRng::from_seed((42u64, 54u64));
+        let v = ~[rng.next_u64(), rng.next_u64(), rng.next_u64(),
+                  rng.next_u64(), rng.next_u64(), rng.next_u64()];
+        assert_eq!(v, ~[0x86b1da1d72062b68, 0x1304aa46c9853d39, 0xa3670e9e0dd50358,
+                        0xf9090e529a7dae00, 0xc85b9fd837996f2c, 0x606121f8e3919196]);
+    }
+
+    #[test]
+    fn test_streams() {
+        let mut a: Pcg32 = SeedableRng::from_seed((1u64, 1u64));
+        let mut b: Pcg32 = SeedableRng::from_seed((1u64, 2u64));
+        assert!(range(0, 8).any(|_| a.next_u32() != b.next_u32()));
+
+        let mut a: Pcg64 = SeedableRng::from_seed((1u64, 1u64));
+        let mut b: Pcg64 = SeedableRng::from_seed((1u64, 2u64));
+        assert!(range(0, 8).any(|_| a.next_u64() != b.next_u64()));
+
+        // reseeding restarts the sequence.
+        a.reseed((1u64, 1u64));
+        b.reseed((1u64, 1u64));
+        assert!(range(0, 8).all(|_| a.next_u64() == b.next_u64()));
+    }
+
+    #[test]
+    fn test_mul_wide() {
+        assert_eq!(mul_wide(0, 12345), (0, 0));
+        assert_eq!(mul_wide(1 << 32, 1 << 32), (1, 0));
+        assert_eq!(mul_wide(-1u64, -1u64), (-2u64, 1));
+        assert_eq!(mul_wide(0x123456789abcdef0, 0xfedcba9876543210),
+                   (0x121fa00ad77d7422, 0x236d88fe5618cf00));
+    }
+}
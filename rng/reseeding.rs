@@ -3,6 +3,7 @@
 
 use Rng;
 use SeedableRng;
+use Error;
 
 /// How many bytes of entropy the underling RNG is allowed to consume
 /// before it is reseeded.
@@ -82,6 +83,31 @@ impl<R: Rng, Rsdr: Reseeder<R>> Rng for ReseedingRng<R, Rsdr> {
     fn entropy_f64(&self) -> uint {
         self.rng.entropy_f64()
     }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.reseed_if_necessary();
+        self.entropy_used += self.rng.entropy_bytes(dest.len());
+        self.rng.fill_bytes(dest)
+    }
+    fn entropy_bytes(&self, len: uint) -> uint {
+        self.rng.entropy_bytes(len)
+    }
+
+    fn try_next_u32(&mut self) -> Result<u32, Error> {
+        self.reseed_if_necessary();
+        self.entropy_used += self.rng.entropy_u32();
+        self.rng.try_next_u32()
+    }
+    fn try_next_u64(&mut self) -> Result<u64, Error> {
+        self.reseed_if_necessary();
+        self.entropy_used += self.rng.entropy_u64();
+        self.rng.try_next_u64()
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.reseed_if_necessary();
+        self.entropy_used += self.rng.entropy_bytes(dest.len());
+        self.rng.try_fill_bytes(dest)
+    }
 }
 
 impl<Seed, R: SeedableRng<Seed>, Rsdr: Reseeder<R>>
@@ -0,0 +1,132 @@
+use Rng;
+use Rand;
+use SeedableRng;
+use rng::seed;
+use std::vec;
+
+/// The SplitMix64 random number generator of Steele, Lea and Flood, as
+/// used by `java.util.SplittableRandom`: a Weyl sequence with a
+/// strong 64-bit mixing function applied to each output. It has only
+/// 64 bits of state, but every seed (including 0) gives a good
+/// sequence, which makes it ideal for expanding a single integer into
+/// the larger seeds of other generators; see `expand_seed`. Not
+/// suitable for cryptographic purposes.
+pub struct SplitMix64 {
+    priv x: u64
+}
+
+impl SplitMix64 {
+    /// Create a SplitMix64 generator with a random seed.
+    pub fn new() -> SplitMix64 {
+        SeedableRng::from_seed(unsafe {seed::<u64>(1)}[0])
+    }
+}
+
+impl Rng for SplitMix64 {
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.x += 0x9e3779b97f4a7c15;
+        let mut z = self.x;
+        z = (z ^ (z >> 30)) * 0xbf58476d1ce4e5b9;
+        z = (z ^ (z >> 27)) * 0x94d049bb133111eb;
+        z ^ (z >> 31)
+    }
+
+    #[inline]
+    fn entropy_u64(&self) -> uint { 8 }
+}
+
+impl SeedableRng<u64> for SplitMix64 {
+    fn reseed(&mut self, seed: u64) {
+        self.x = seed;
+    }
+
+    fn from_seed(seed: u64) -> SplitMix64 {
+        SplitMix64 { x: seed }
+    }
+}
+
+/// Expand the single integer `seed` into `len` random values, using
+/// `SplitMix64`. This is used by the `seed_from_u64` constructors of
+/// the generators in this module, so that every bit of their state
+/// depends on every bit of `seed`.
+///
+/// # Example
+///
+/// ~~~ {.rust}
+/// use rand::SeedableRng;
+/// use rand::rng::{WELL512, expand_seed};
+///
+/// fn main() {
+///     let seed = expand_seed::<u32>(42, 16);
+///     let rng: WELL512 = SeedableRng::from_seed(seed.as_slice());
+/// }
+/// ~~~
+pub fn expand_seed<T: Rand>(seed: u64, len: uint) -> ~[T] {
+    let mut rng: SplitMix64 = SeedableRng::from_seed(seed);
+    vec::from_fn(len, |_| rng.gen())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use Rng;
+    use SeedableRng;
+    use rng::{StdRng, Isaac, Isaac64, MT19937, MT19937_64, WELL512, LFSR258, LFSR113, Taus88};
+    use rng::{XorShift4, Xoshiro256StarStar, Xoshiro256Plus, Xoroshiro128Plus};
+    use rng::{MinStd_Rand, Rand48, CMWC, MWC256, Pcg32, Pcg64, ChaChaRng, Hc128Rng};
+
+    #[test]
+    fn test_splitmix64_reference() {
+        // from the reference C implementation at
+        // http://xoshiro.di.unimi.it/splitmix64.c
+        let mut rng: SplitMix64 = SeedableRng::from_seed(1234567u64);
+        let v = ~[rng.next_u64(), rng.next_u64(), rng.next_u64(),
+                  rng.next_u64(), rng.next_u64()];
+        assert_eq!(v, ~[6457827717110365317, 3203168211198807973, 9817491932198370423,
+                        4593380528125082431, 16408922859458223821]);
+    }
+
+    #[test]
+    fn test_expand_seed() {
+        assert_eq!(expand_seed::<u64>(1234567, 2),
+                   ~[6457827717110365317, 3203168211198807973]);
+        assert_eq!(expand_seed::<u32>(0, 0), ~[]);
+        assert!(expand_seed::<u32>(0, 4).iter().all(|&x| x != 0));
+    }
+
+    #[test]
+    fn test_seed_from_u64() {
+        macro_rules! t (
+            ($($rng:ident),*) => {{
+                $(
+                    let mut a = $rng::seed_from_u64(0);
+                    let mut b = $rng::seed_from_u64(0);
+                    let mut c = $rng::seed_from_u64(1);
+                    let xs = ~[a.next_u64(), a.next_u64()];
+                    assert_eq!(xs, ~[b.next_u64(), b.next_u64()]);
+                    assert!(xs != ~[c.next_u64(), c.next_u64()]);
+                 )*
+            }}
+        );
+        t!(StdRng, Isaac, Isaac64, MT19937, MT19937_64, WELL512, LFSR258, LFSR113, Taus88,
+           XorShift4, Xoshiro256StarStar, Xoshiro256Plus, Xoroshiro128Plus, MinStd_Rand,
+           Rand48, CMWC, MWC256, Pcg32, Pcg64, ChaChaRng, Hc128Rng)
+    }
+
+    #[test]
+    fn test_isaac_u32_seed_expanded() {
+        // a single `u32` seed is expanded, rather than being followed
+        // by zeros.
+        let mut a: Isaac = SeedableRng::from_seed(7u32);
+        let mut b = Isaac::seed_from_u64(7);
+        for _ in range(0, 10) {
+            assert_eq!(a.next_u32(), b.next_u32());
+        }
+        let mut a: Isaac64 = SeedableRng::from_seed(7u64);
+        let mut b = Isaac64::seed_from_u64(7);
+        for _ in range(0, 10) {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+}
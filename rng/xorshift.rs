@@ -1,5 +1,6 @@
 use Rng;
 use SeedableRng;
+use rng::{seed, expand_seed};
 
 /// An [Xorshift random number
 /// generator](http://en.wikipedia.org/wiki/Xorshift). Not suitable for
@@ -17,6 +18,13 @@ impl XorShift4 {
         // constants taken from http://en.wikipedia.org/wiki/Xorshift
         SeedableRng::from_seed([123456789, 362436069, 521288629, 88675123])
     }
+
+    /// Create an xor shift random number generator from a single
+    /// `u64`, expanded into a full seed with `SplitMix64`.
+    pub fn seed_from_u64(seed: u64) -> XorShift4 {
+        let s = expand_seed::<u32>(seed, 4);
+        SeedableRng::from_seed([s[0], s[1], s[2], s[3]])
+    }
 }
 
 impl Rng for XorShift4 {
@@ -56,3 +64,354 @@ impl SeedableRng<[u32, .. 4]> for XorShift4 {
         r
     }
 }
+
+#[inline]
+fn rotl(x: u64, k: uint) -> u64 {
+    (x << k) | (x >> (64 - k))
+}
+
+// the xoshiro/xoroshiro generators are stuck at zero if seeded with
+// it.
+static ZERO_SEED_MSG: &'static str = "xoshiro/xoroshiro seeds must not be all zero";
+
+// the polynomials for jumping 2^128 and 2^192 steps ahead, from the
+// reference implementation.
+static XOSHIRO256_JUMP: [u64, .. 4] = [0x180ec6d33cfd0aba, 0xd5a61266f0c9392c,
+                                       0xa9582618e03fc9aa, 0x39abdc4529b1661c];
+static XOSHIRO256_LONG_JUMP: [u64, .. 4] = [0x76e15d3efefdcbbf, 0xc5004e441c522fb3,
+                                            0x77710069854ee241, 0x39109bb02acbe635];
+
+#[inline]
+fn xoshiro256_step(s: &mut [u64, .. 4]) {
+    let t = s[1] << 17;
+    s[2] ^= s[0];
+    s[3] ^= s[1];
+    s[1] ^= s[2];
+    s[0] ^= s[3];
+    s[2] ^= t;
+    s[3] = rotl(s[3], 45);
+}
+
+/// Advance `s` by the number of steps encoded by `table`, by summing
+/// (xoring) the states reached at the bits set in it.
+fn xoshiro256_jump(s: &mut [u64, .. 4], table: &[u64, .. 4]) {
+    let mut acc = [0u64, .. 4];
+    for &word in table.iter() {
+        for b in range(0, 64) {
+            if word & (1 << b) != 0 {
+                for i in range(0u, 4) {
+                    acc[i] ^= s[i];
+                }
+            }
+            xoshiro256_step(s);
+        }
+    }
+    *s = acc;
+}
+
+/// The [xoshiro256**](http://xoshiro.di.unimi.it/) random number
+/// generator of Blackman and Vigna, with 256 bits of state and a
+/// period of 2^256 - 1. This is a good general-purpose generator,
+/// passing all known statistical tests, but is not suitable for
+/// cryptographic purposes.
+///
+/// The `jump` and `long_jump` methods split the period into
+/// non-overlapping subsequences, e.g. for parallel computations.
+pub struct Xoshiro256StarStar {
+    priv s: [u64, .. 4]
+}
+
+impl Xoshiro256StarStar {
+    /// Create a xoshiro256** random number generator with a random seed.
+    pub fn new() -> Xoshiro256StarStar {
+        let s = unsafe { seed::<u64>(4) };
+        SeedableRng::from_seed([s[0], s[1], s[2], s[3]])
+    }
+
+    /// Create a xoshiro256** random number generator from a single `u64`,
+    /// expanded into a full seed with `SplitMix64`. This is the
+    /// seeding recommended by the authors.
+    pub fn seed_from_u64(seed: u64) -> Xoshiro256StarStar {
+        let s = expand_seed::<u64>(seed, 4);
+        SeedableRng::from_seed([s[0], s[1], s[2], s[3]])
+    }
+
+    /// Advance the generator by 2^128 steps. Calling this repeatedly on
+    /// copies of one generator gives 2^128 non-overlapping
+    /// subsequences, each of length 2^128.
+    pub fn jump(&mut self) {
+        xoshiro256_jump(&mut self.s, &XOSHIRO256_JUMP)
+    }
+
+    /// Advance the generator by 2^192 steps, giving 2^64 starting
+    /// points, from each of which `jump` gives a further 2^64
+    /// non-overlapping subsequences.
+    pub fn long_jump(&mut self) {
+        xoshiro256_jump(&mut self.s, &XOSHIRO256_LONG_JUMP)
+    }
+}
+
+impl Rng for Xoshiro256StarStar {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    #[inline]
+    fn entropy_u32(&self) -> uint { 8 }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let result = rotl(self.s[1] * 5, 7) * 9;
+        xoshiro256_step(&mut self.s);
+        result
+    }
+
+    #[inline]
+    fn entropy_u64(&self) -> uint { 8 }
+}
+
+impl SeedableRng<[u64, .. 4]> for Xoshiro256StarStar {
+    fn reseed(&mut self, seed: [u64, .. 4]) {
+        assert!(seed.iter().any(|&x| x != 0), ZERO_SEED_MSG);
+        self.s = seed;
+    }
+
+    /// Create a xoshiro256** random number generator using the
+    /// specified seed, which must not be all zero.
+    fn from_seed(seed: [u64, .. 4]) -> Xoshiro256StarStar {
+        let mut r = Xoshiro256StarStar { s: [0, .. 4] };
+        r.reseed(seed);
+        r
+    }
+}
+
+/// The [xoshiro256+](http://xoshiro.di.unimi.it/) random number
+/// generator of Blackman and Vigna. This uses the same state as
+/// xoshiro256**, with a faster but weaker output function: the lowest
+/// bits of each `u64` have low linear complexity, so it is best
+/// suited to generating floating point numbers (which only use the
+/// high bits). Not suitable for cryptographic purposes.
+pub struct Xoshiro256Plus {
+    priv s: [u64, .. 4]
+}
+
+impl Xoshiro256Plus {
+    /// Create a xoshiro256+ random number generator with a random seed.
+    pub fn new() -> Xoshiro256Plus {
+        let s = unsafe { seed::<u64>(4) };
+        SeedableRng::from_seed([s[0], s[1], s[2], s[3]])
+    }
+
+    /// Create a xoshiro256+ random number generator from a single `u64`,
+    /// expanded into a full seed with `SplitMix64`. This is the
+    /// seeding recommended by the authors.
+    pub fn seed_from_u64(seed: u64) -> Xoshiro256Plus {
+        let s = expand_seed::<u64>(seed, 4);
+        SeedableRng::from_seed([s[0], s[1], s[2], s[3]])
+    }
+
+    /// Advance the generator by 2^128 steps. See
+    /// `Xoshiro256StarStar::jump`.
+    pub fn jump(&mut self) {
+        xoshiro256_jump(&mut self.s, &XOSHIRO256_JUMP)
+    }
+
+    /// Advance the generator by 2^192 steps. See
+    /// `Xoshiro256StarStar::long_jump`.
+    pub fn long_jump(&mut self) {
+        xoshiro256_jump(&mut self.s, &XOSHIRO256_LONG_JUMP)
+    }
+}
+
+impl Rng for Xoshiro256Plus {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    #[inline]
+    fn entropy_u32(&self) -> uint { 8 }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let result = self.s[0] + self.s[3];
+        xoshiro256_step(&mut self.s);
+        result
+    }
+
+    #[inline]
+    fn entropy_u64(&self) -> uint { 8 }
+}
+
+impl SeedableRng<[u64, .. 4]> for Xoshiro256Plus {
+    fn reseed(&mut self, seed: [u64, .. 4]) {
+        assert!(seed.iter().any(|&x| x != 0), ZERO_SEED_MSG);
+        self.s = seed;
+    }
+
+    /// Create a xoshiro256+ random number generator using the
+    /// specified seed, which must not be all zero.
+    fn from_seed(seed: [u64, .. 4]) -> Xoshiro256Plus {
+        let mut r = Xoshiro256Plus { s: [0, .. 4] };
+        r.reseed(seed);
+        r
+    }
+}
+
+// the polynomials for jumping 2^64 and 2^96 steps ahead, from the
+// reference implementation.
+static XOROSHIRO128_JUMP: [u64, .. 2] = [0xdf900294d8f554a5, 0x170865df4b3201fc];
+static XOROSHIRO128_LONG_JUMP: [u64, .. 2] = [0xd2a98b26625eee7b, 0xdddf9b1090aa7ac1];
+
+/// The [xoroshiro128+](http://xoshiro.di.unimi.it/) random number
+/// generator of Blackman and Vigna, with 128 bits of state, a period
+/// of 2^128 - 1 and the (24, 16, 37) parameters. Like `Xoshiro256Plus`
+/// the lowest bits of its output are weak. Not suitable for
+/// cryptographic purposes.
+pub struct Xoroshiro128Plus {
+    priv s0: u64,
+    priv s1: u64
+}
+
+impl Xoroshiro128Plus {
+    /// Create a xoroshiro128+ random number generator with a random
+    /// seed.
+    pub fn new() -> Xoroshiro128Plus {
+        let s = unsafe { seed::<u64>(2) };
+        SeedableRng::from_seed([s[0], s[1]])
+    }
+
+    /// Create a xoroshiro128+ random number generator from a single
+    /// `u64`, expanded into a full seed with `SplitMix64`.
+    pub fn seed_from_u64(seed: u64) -> Xoroshiro128Plus {
+        let s = expand_seed::<u64>(seed, 2);
+        SeedableRng::from_seed([s[0], s[1]])
+    }
+
+    #[inline]
+    fn step(&mut self) {
+        let s1 = self.s1 ^ self.s0;
+        self.s0 = rotl(self.s0, 24) ^ s1 ^ (s1 << 16);
+        self.s1 = rotl(s1, 37);
+    }
+
+    fn jump_with(&mut self, table: &[u64, .. 2]) {
+        let (mut s0, mut s1) = (0, 0);
+        for &word in table.iter() {
+            for b in range(0, 64) {
+                if word & (1 << b) != 0 {
+                    s0 ^= self.s0;
+                    s1 ^= self.s1;
+                }
+                self.step();
+            }
+        }
+        self.s0 = s0;
+        self.s1 = s1;
+    }
+
+    /// Advance the generator by 2^64 steps. Calling this repeatedly on
+    /// copies of one generator gives 2^64 non-overlapping
+    /// subsequences, each of length 2^64.
+    pub fn jump(&mut self) {
+        self.jump_with(&XOROSHIRO128_JUMP)
+    }
+
+    /// Advance the generator by 2^96 steps, giving 2^32 starting
+    /// points, from each of which `jump` gives a further 2^32
+    /// non-overlapping subsequences.
+    pub fn long_jump(&mut self) {
+        self.jump_with(&XOROSHIRO128_LONG_JUMP)
+    }
+}
+
+impl Rng for Xoroshiro128Plus {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    #[inline]
+    fn entropy_u32(&self) -> uint { 8 }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        let result = self.s0 + self.s1;
+        self.step();
+        result
+    }
+
+    #[inline]
+    fn entropy_u64(&self) -> uint { 8 }
+}
+
+impl SeedableRng<[u64, .. 2]> for Xoroshiro128Plus {
+    fn reseed(&mut self, seed: [u64, .. 2]) {
+        assert!(seed.iter().any(|&x| x != 0), ZERO_SEED_MSG);
+        self.s0 = seed[0];
+        self.s1 = seed[1];
+    }
+
+    /// Create a xoroshiro128+ random number generator using the
+    /// specified seed, which must not be all zero.
+    fn from_seed(seed: [u64, .. 2]) -> Xoroshiro128Plus {
+        let mut r = Xoroshiro128Plus { s0: 0, s1: 0 };
+        r.reseed(seed);
+        r
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use Rng;
+    use SeedableRng;
+
+    // the reference outputs were generated with the C implementations
+    // at http://xoshiro.di.unimi.it/.
+
+    #[test]
+    fn test_xoshiro256starstar() {
+        let mut rng: Xoshiro256StarStar = SeedableRng::from_seed([1, 2, 3, 4]);
+        let v = ~[rng.next_u64(), rng.next_u64(), rng.next_u64(), rng.next_u64()];
+        assert_eq!(v, ~[11520, 0, 1509978240, 1215971899390074240]);
+
+        let mut rng: Xoshiro256StarStar = SeedableRng::from_seed([1, 2, 3, 4]);
+        rng.jump();
+        assert_eq!(rng.next_u64(), 0xbbd2f312298443d8);
+        assert_eq!(rng.next_u64(), 0x62e57db2d5706577);
+    }
+
+    #[test]
+    fn test_xoshiro256plus() {
+        let mut rng: Xoshiro256Plus = SeedableRng::from_seed([1, 2, 3, 4]);
+        let v = ~[rng.next_u64(), rng.next_u64(), rng.next_u64(), rng.next_u64()];
+        assert_eq!(v, ~[5, 211106232532999, 211106635186183, 9223759065350669058]);
+
+        let mut rng: Xoshiro256Plus = SeedableRng::from_seed([1, 2, 3, 4]);
+        rng.long_jump();
+        assert_eq!(rng.next_u64(), 0x3acfeb58b4b6fff1);
+        assert_eq!(rng.next_u64(), 0xa7d498daf861c3cc);
+    }
+
+    #[test]
+    fn test_xoroshiro128plus() {
+        let mut rng: Xoroshiro128Plus = SeedableRng::from_seed([1, 2]);
+        let v = ~[rng.next_u64(), rng.next_u64(), rng.next_u64(), rng.next_u64()];
+        assert_eq!(v, ~[3, 412333834243, 2360170716294286339, 9295852285959843169]);
+
+        let mut rng: Xoroshiro128Plus = SeedableRng::from_seed([1, 2]);
+        rng.jump();
+        assert_eq!(rng.next_u64(), 0xea081299d29ad927);
+        let mut rng: Xoroshiro128Plus = SeedableRng::from_seed([1, 2]);
+        rng.long_jump();
+        assert_eq!(rng.next_u64(), 0x6786a13daa9b187d);
+    }
+
+    #[test]
+    #[should_fail]
+    fn test_zero_seed() {
+        let _rng: Xoshiro256StarStar = SeedableRng::from_seed([0, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,157 @@
+//! Lazily generated random permutations.
+
+use std::hashmap::HashMap;
+use Rng;
+
+/// An iterator over a random permutation of `0, 1, ..., n - 1`,
+/// generated one element at a time.
+///
+/// This runs a Fisher-Yates shuffle lazily, storing only the
+/// positions that have been swapped in a hash map rather than an
+/// array of all `n` indices. Taking the first `k` elements takes time
+/// and memory proportional to `k`, even for a huge `n`.
+///
+/// # Example
+///
+/// ~~~ {.rust}
+/// use std::rand;
+/// use std::rand::shuffle::ShuffleIter;
+///
+/// fn main() {
+///     // the first 3 elements of a random permutation of a billion.
+///     for i in ShuffleIter::new(rand::rng(), 1_000_000_000).take(3) {
+///         printfln!(i);
+///     }
+/// }
+/// ~~~
+pub struct ShuffleIter<R> {
+    priv rng: R,
+    priv n: uint,
+    // the number of elements yielded so far.
+    priv i: uint,
+    // the positions >= i whose elements differ from the identity
+    // permutation, mapped to their elements.
+    priv swapped: HashMap<uint, uint>
+}
+
+impl<R: Rng> ShuffleIter<R> {
+    /// Create an iterator over a random permutation of `0, 1, ..., n -
+    /// 1`, using `rng` as the source of randomness.
+    pub fn new(rng: R, n: uint) -> ShuffleIter<R> {
+        ShuffleIter { rng: rng, n: n, i: 0, swapped: HashMap::new() }
+    }
+}
+
+impl<R: Rng> Iterator<uint> for ShuffleIter<R> {
+    fn next(&mut self) -> Option<uint> {
+        if self.i >= self.n {
+            return None;
+        }
+
+        // swap the elements at positions i and j of the (virtual)
+        // array, and yield the one that ends up at i.
+        let i = self.i;
+        let j = self.rng.gen_range(i, self.n);
+        let at_i = self.swapped.pop(&i).unwrap_or(i);
+        let at_j = if j == i {
+            at_i
+        } else {
+            let at_j = self.swapped.find(&j).map_default(j, |&x| x);
+            self.swapped.insert(j, at_i);
+            at_j
+        };
+        self.i += 1;
+        Some(at_j)
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        let left = self.n - self.i;
+        (left, Some(left))
+    }
+}
+
+/// An iterator over the elements of a slice in a random order,
+/// generated one element at a time. See `ShuffleIter`.
+pub struct ShuffleSliceIter<'self, T, R> {
+    priv values: &'self [T],
+    priv indices: ShuffleIter<R>
+}
+
+impl<'self, T, R: Rng> ShuffleSliceIter<'self, T, R> {
+    /// Create an iterator over the elements of `values` in a random
+    /// order, using `rng` as the source of randomness.
+    pub fn new(rng: R, values: &'self [T]) -> ShuffleSliceIter<'self, T, R> {
+        ShuffleSliceIter { values: values, indices: ShuffleIter::new(rng, values.len()) }
+    }
+}
+
+impl<'self, T, R: Rng> Iterator<&'self T> for ShuffleSliceIter<'self, T, R> {
+    fn next(&mut self) -> Option<&'self T> {
+        self.indices.next().map(|i| &self.values[i])
+    }
+
+    fn size_hint(&self) -> (uint, Option<uint>) {
+        self.indices.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec;
+    use SeedableRng;
+    use rng::Isaac64;
+
+    #[test]
+    fn test_shuffle_iter_permutation() {
+        for n in range(0u, 20) {
+            let mut seen = vec::from_elem(n, false);
+            let mut iter = ShuffleIter::new(::rng(), n);
+            assert_eq!(iter.size_hint(), (n, Some(n)));
+            for i in iter {
+                assert!(!seen[i]);
+                seen[i] = true;
+            }
+            assert!(seen.iter().all(|&x| x));
+        }
+    }
+
+    #[test]
+    fn test_shuffle_iter_uniform() {
+        // each element should be equally likely in each position.
+        let n = 5;
+        let trials = 10000u;
+        let mut counts = vec::from_elem(n * n, 0u);
+        for trial in range(0, trials) {
+            let rng: Isaac64 = SeedableRng::from_seed(trial as u64);
+            let iter = ShuffleIter::new(rng, n);
+            for (position, x) in iter.enumerate() {
+                counts[position * n + x] += 1;
+            }
+        }
+        let p = 1.0 / n as f64;
+        let expected = trials as f64 * p;
+        let sd = (trials as f64 * p * (1.0 - p)).sqrt();
+        for &count in counts.iter() {
+            assert!((count as f64 - expected).abs() <= 6.0 * sd,
+                    "got {}, expected {}", count, expected);
+        }
+    }
+
+    #[test]
+    fn test_shuffle_iter_huge() {
+        let first: ~[uint] = ShuffleIter::new(::rng(), 1_000_000_000).take(100).collect();
+        assert_eq!(first.len(), 100);
+        assert!(first.iter().all(|&x| x < 1_000_000_000));
+    }
+
+    #[test]
+    fn test_shuffle_slice_iter() {
+        let v = ['a', 'b', 'c', 'd'];
+        let shuffled: ~[&char] = ShuffleSliceIter::new(::rng(), v).collect();
+        assert_eq!(shuffled.len(), 4);
+        for c in v.iter() {
+            assert!(shuffled.contains(&c));
+        }
+    }
+}